@@ -0,0 +1,28 @@
+#!/bin/bash
+
+# Fetch the barretenberg C++ sources bundled by the `vendored` feature
+# Run once from anywhere; the tree lands in barretenberg-src/barretenberg/cpp
+
+set -e  # Exit on any error
+
+CRATE_DIR="$(cd "$(dirname "$0")" && pwd)"
+AZTEC_TAG="v$(cat "$CRATE_DIR/VERSION")"
+DEST="$CRATE_DIR/barretenberg"
+
+if [ -d "$DEST/cpp" ]; then
+    echo "✅ Sources already present: $DEST/cpp"
+    exit 0
+fi
+
+TMP="$(mktemp -d)"
+trap 'rm -rf "$TMP"' EXIT
+
+echo "📥 Fetching barretenberg from aztec-packages $AZTEC_TAG..."
+git clone --depth 1 --branch "$AZTEC_TAG" --filter=blob:none --sparse \
+    https://github.com/AztecProtocol/aztec-packages.git "$TMP/aztec-packages"
+git -C "$TMP/aztec-packages" sparse-checkout set barretenberg/cpp
+
+mkdir -p "$DEST"
+mv "$TMP/aztec-packages/barretenberg/cpp" "$DEST/cpp"
+
+echo "✅ Sources ready: $DEST/cpp"
@@ -0,0 +1,139 @@
+//! Barretenberg C++ sources and a CMake driver for the `vendored` feature
+//!
+//! Meant to be used from a build script:
+//!
+//! ```ignore
+//! let artifacts = barretenberg_src::Build::new().build();
+//! ```
+//!
+//! The sources are not committed to git: the published crate ships them, but
+//! a git checkout has to run `fetch-source.sh` once (which needs network
+//! access) or set `BARRETENBERG_SRC_DIR` to an existing `barretenberg/cpp`
+//! tree. Only after that do `vendored` builds work offline.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Libraries and headers produced by a vendored build
+pub struct Artifacts {
+    pub lib_dir: PathBuf,
+    pub include_dirs: Vec<PathBuf>,
+    /// barretenberg release of the bundled tree, `None` for `BARRETENBERG_SRC_DIR`
+    pub version: Option<String>,
+}
+
+/// CMake build of the bundled barretenberg tree
+pub struct Build {
+    source_dir: PathBuf,
+    out_dir: Option<PathBuf>,
+}
+
+/// The `barretenberg/cpp` directory to build: `BARRETENBERG_SRC_DIR` if set, the bundled one otherwise
+pub fn source_dir() -> PathBuf {
+    env::var_os("BARRETENBERG_SRC_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("barretenberg/cpp"))
+}
+
+/// barretenberg release the bundled sources are pinned to
+pub const VERSION: &str = include_str!("../VERSION");
+
+/// Release of the tree `source_dir()` points at, when it is the bundled one
+fn source_version() -> Option<String> {
+    env::var_os("BARRETENBERG_SRC_DIR")
+        .is_none()
+        .then(|| VERSION.trim().to_string())
+}
+
+impl Default for Build {
+    fn default() -> Self {
+        Build::new()
+    }
+}
+
+impl Build {
+    pub fn new() -> Self {
+        Build {
+            source_dir: source_dir(),
+            out_dir: env::var_os("OUT_DIR").map(PathBuf::from),
+        }
+    }
+
+    /// Build into `out_dir` instead of the calling build script's `OUT_DIR`
+    pub fn out_dir<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.out_dir = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Configure and build barretenberg, reusing a previous build in `out_dir`
+    ///
+    /// # Panics
+    ///
+    /// If the sources were never fetched (and `BARRETENBERG_SRC_DIR` is unset) or CMake fails.
+    pub fn build(&mut self) -> Artifacts {
+        let out_dir = self
+            .out_dir
+            .clone()
+            .expect("OUT_DIR not set, call Build::out_dir");
+        if !self.source_dir.join("CMakeLists.txt").is_file() {
+            panic!(
+                "Barretenberg sources not found at {}. They are not part of the git repository: \
+                 run barretenberg-src/fetch-source.sh once (needs network access), \
+                 or set BARRETENBERG_SRC_DIR to a barretenberg/cpp checkout.",
+                self.source_dir.display()
+            );
+        }
+
+        let build_dir = out_dir.join("build");
+        let lib_dir = build_dir.join("lib");
+        let include_dir = build_dir.join("include");
+        let stamp = out_dir.join("barretenberg.stamp");
+        let version = source_version();
+
+        let stamp_contents = format!(
+            "{}\n{}",
+            self.source_dir.display(),
+            version.as_deref().unwrap_or("unknown")
+        );
+        let cached = lib_dir.join("libbarretenberg.a").is_file()
+            && lib_dir.join("libenv.a").is_file()
+            && fs::read_to_string(&stamp).is_ok_and(|built| built == stamp_contents);
+        if !cached {
+            cmake::Config::new(&self.source_dir)
+                .out_dir(&out_dir)
+                .profile("Release")
+                .define("TRACY_ENABLE", "OFF")
+                .define("STARKNET_GARAGA_FLAVORS", "ON")
+                .build_target("bb")
+                .build();
+            fs::write(&stamp, &stamp_contents).expect("Couldn't write build stamp");
+        }
+
+        if !include_dir.exists() {
+            let status = Command::new("sh")
+                .arg(self.source_dir.join("copy-headers.sh"))
+                .arg(&include_dir)
+                .current_dir(&self.source_dir)
+                .status()
+                .expect("Failed to run copy-headers.sh");
+            assert!(status.success(), "copy-headers.sh failed");
+        }
+
+        let include_dirs = [
+            include_dir,
+            build_dir.join("_deps/tracy-src/public"),
+            build_dir.join("_deps/msgpack-c/src/msgpack-c/include"),
+        ]
+        .into_iter()
+        .filter(|dir| dir.exists())
+        .collect();
+
+        Artifacts {
+            lib_dir,
+            include_dirs,
+            version,
+        }
+    }
+}
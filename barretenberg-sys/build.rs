@@ -0,0 +1,515 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+#[cfg(all(feature = "vendored", feature = "dynamic"))]
+compile_error!("the `vendored` build is static, it cannot be combined with `dynamic`");
+
+/// The barretenberg library file to look for
+#[cfg(feature = "dynamic")]
+const LIB_FILE: &str = "libbarretenberg.so";
+#[cfg(not(feature = "dynamic"))]
+const LIB_FILE: &str = "libbarretenberg.a";
+
+/// Where the barretenberg library and headers live
+struct BarretenbergPaths {
+    lib_dirs: Vec<PathBuf>,
+    include_dirs: Vec<PathBuf>,
+    /// Version reported by the lookup itself, e.g. pkg-config
+    version: Option<String>,
+}
+
+#[cfg(not(feature = "vendored"))]
+fn has_barretenberg(dir: &Path) -> bool {
+    dir.join(LIB_FILE).is_file()
+}
+
+/// Look up `key` in the `CMakeCache.txt` of the build tree next to `lib_dirs`
+fn cmake_cache_value(lib_dirs: &[PathBuf], key: &str) -> Option<String> {
+    let prefix = format!("{}=", key);
+    lib_dirs
+        .iter()
+        .filter_map(|dir| std::fs::read_to_string(dir.parent()?.join("CMakeCache.txt")).ok())
+        .find_map(|cache| {
+            cache
+                .lines()
+                .find_map(|line| line.strip_prefix(&prefix).map(str::to_string))
+        })
+}
+
+/// Version of the located barretenberg, from `BB_VERSION`, the lookup (pkg-config) or the cmake cache
+fn barretenberg_version(barretenberg: &BarretenbergPaths) -> String {
+    env::var("BB_VERSION")
+        .ok()
+        .or_else(|| barretenberg.version.clone())
+        .or_else(|| cmake_cache_value(&barretenberg.lib_dirs, "CMAKE_PROJECT_VERSION:STATIC"))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Commit of the barretenberg sources, from `BB_GIT_HASH` or the source tree
+fn barretenberg_git_hash(lib_dirs: &[PathBuf]) -> String {
+    if let Ok(hash) = env::var("BB_GIT_HASH") {
+        return hash;
+    }
+    cmake_cache_value(lib_dirs, "CMAKE_HOME_DIRECTORY:INTERNAL")
+        .and_then(|source_dir| {
+            std::process::Command::new("git")
+                .args(["-C", &source_dir, "rev-parse", "--short=12", "HEAD"])
+                .output()
+                .ok()
+        })
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Whether the located library exports the Starknet transcript entry points,
+/// which barretenberg only compiles with `-DSTARKNET_GARAGA_FLAVORS=ON`
+///
+/// Both archive and shared object keep symbol names as NUL-terminated strings.
+fn has_starknet_flavors(lib_dirs: &[PathBuf]) -> bool {
+    let symbol = b"acir_prove_ultra_starknet_honk\0";
+    lib_dirs
+        .iter()
+        .filter_map(|dir| std::fs::read(dir.join(LIB_FILE)).ok())
+        .any(|library| library.windows(symbol.len()).any(|window| window == symbol))
+}
+
+#[cfg(not(feature = "dynamic"))]
+fn find_library(lib_dirs: &[PathBuf], lib_name: &str) -> Option<PathBuf> {
+    let mut search_paths = Vec::new();
+    for dir in lib_dirs {
+        search_paths.push(dir.clone());
+        // A cmake build dir keeps its dependencies next to `lib/`
+        if let Some(build_dir) = dir.parent() {
+            search_paths.push(build_dir.join("_deps").join(format!("{}-build", lib_name)));
+            search_paths.push(build_dir.join("_deps").join(lib_name).join("build"));
+        }
+    }
+
+    for path in search_paths {
+        if path.exists() {
+            // Look for .a files
+            if let Ok(entries) = std::fs::read_dir(&path) {
+                for entry in entries.flatten() {
+                    let file_name = entry.file_name();
+                    let file_str = file_name.to_string_lossy();
+                    if file_str.contains(lib_name) && file_str.ends_with(".a") {
+                        println!("cargo:warning=Found {} at {:?}", lib_name, entry.path());
+                        return Some(path);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Copy the public headers into `include_dir` with barretenberg's own script
+#[cfg(not(feature = "vendored"))]
+fn copy_headers(cpp_dir: &Path, include_dir: &Path) {
+    use std::process::Command;
+
+    let script = cpp_dir.join("copy-headers.sh");
+    if include_dir.exists() || !script.is_file() {
+        return;
+    }
+
+    let result = Command::new("sh")
+        .arg(&script)
+        .arg(include_dir)
+        .current_dir(cpp_dir)
+        .output();
+
+    match result {
+        Ok(output) => {
+            if !output.status.success() {
+                println!(
+                    "cargo:warning=copy-headers.sh failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            } else {
+                println!("cargo:warning=Headers copied successfully");
+            }
+        }
+        Err(e) => {
+            println!("cargo:warning=Failed to run copy-headers.sh: {}", e);
+        }
+    }
+}
+
+/// Paths inside a cmake build directory (`barretenberg/cpp/build`)
+#[cfg(not(feature = "vendored"))]
+fn from_build_dir(build_dir: &Path) -> BarretenbergPaths {
+    let include_dir = build_dir.join("include");
+    if let Some(cpp_dir) = build_dir.parent() {
+        copy_headers(cpp_dir, &include_dir);
+    }
+
+    let include_dirs = [
+        include_dir,
+        build_dir.join("_deps/tracy-src/public"),
+        build_dir.join("_deps/msgpack-c/src/msgpack-c/include"),
+    ]
+    .into_iter()
+    .filter(|dir| dir.exists())
+    .collect();
+
+    BarretenbergPaths {
+        lib_dirs: vec![build_dir.join("lib")],
+        include_dirs,
+        version: None,
+    }
+}
+
+/// Locate barretenberg, in order of precedence:
+///
+/// 1. `BB_LIB_DIR`, plus `BB_INCLUDE_DIR` (possibly several dirs) for regenerating bindings
+/// 2. `BARRETENBERG_DIR`, either `barretenberg/cpp` or its `build` directory
+/// 3. a `barretenberg` pkg-config package
+///
+/// Panics with every location tried when nothing is found.
+#[cfg(not(feature = "vendored"))]
+fn locate_barretenberg() -> BarretenbergPaths {
+    let mut searched = Vec::new();
+
+    // Headers are only needed to regenerate the bindings
+    match env::var_os("BB_LIB_DIR") {
+        Some(lib_dir) => {
+            let lib_dir = PathBuf::from(lib_dir);
+            if has_barretenberg(&lib_dir) {
+                return BarretenbergPaths {
+                    lib_dirs: vec![lib_dir],
+                    include_dirs: env::var_os("BB_INCLUDE_DIR")
+                        .map(|dirs| env::split_paths(&dirs).collect())
+                        .unwrap_or_default(),
+                    version: None,
+                };
+            }
+            searched.push(format!(
+                "BB_LIB_DIR={}: {} not found",
+                lib_dir.display(),
+                LIB_FILE
+            ));
+        }
+        None => searched.push("BB_LIB_DIR: not set".to_string()),
+    }
+
+    match env::var_os("BARRETENBERG_DIR") {
+        Some(dir) => {
+            let dir = PathBuf::from(dir);
+            for build_dir in [dir.clone(), dir.join("build")] {
+                if has_barretenberg(&build_dir.join("lib")) {
+                    return from_build_dir(&build_dir);
+                }
+                searched.push(format!(
+                    "BARRETENBERG_DIR: {}/lib/{} not found",
+                    build_dir.display(),
+                    LIB_FILE
+                ));
+            }
+        }
+        None => searched.push("BARRETENBERG_DIR: not set".to_string()),
+    }
+
+    match pkg_config::Config::new()
+        .cargo_metadata(false)
+        .probe("barretenberg")
+    {
+        Ok(library) => {
+            return BarretenbergPaths {
+                lib_dirs: library.link_paths,
+                include_dirs: library.include_paths,
+                version: Some(library.version).filter(|version| !version.is_empty()),
+            };
+        }
+        Err(e) => searched.push(format!("pkg-config barretenberg: {}", e)),
+    }
+
+    panic!(
+        "Could not find barretenberg. Searched:\n  - {}\n\
+         Enable the `vendored` feature, or build it with build_barretenberg.sh and set BARRETENBERG_DIR to barretenberg/cpp, \
+         or set BB_LIB_DIR.",
+        searched.join("\n  - ")
+    );
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    for var in [
+        "BARRETENBERG_DIR",
+        "BB_LIB_DIR",
+        "BB_INCLUDE_DIR",
+        "PKG_CONFIG_PATH",
+        "BB_UPDATE_BINDINGS",
+        "BB_VERSION",
+        "BB_GIT_HASH",
+        "BB_NO_RPATH",
+        "BARRETENBERG_SRC_DIR",
+    ] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+
+    #[cfg(feature = "vendored")]
+    let barretenberg = {
+        let artifacts = barretenberg_src::Build::new().build();
+        BarretenbergPaths {
+            lib_dirs: vec![artifacts.lib_dir],
+            include_dirs: artifacts.include_dirs,
+            version: artifacts.version,
+        }
+    };
+    #[cfg(not(feature = "vendored"))]
+    let barretenberg = locate_barretenberg();
+
+    // Recorded for `barretenberg::version()` and artifact envelopes
+    let version = barretenberg_version(&barretenberg);
+    println!("cargo:rustc-env=BB_LINKED_VERSION={}", version);
+    println!(
+        "cargo:rustc-env=BB_LINKED_GIT_HASH={}",
+        barretenberg_git_hash(&barretenberg.lib_dirs)
+    );
+
+    // `bb_starknet` gates the Starknet entry points, here and (through
+    // DEP_BARRETENBERG_STARKNET) in dependents
+    println!("cargo:rustc-check-cfg=cfg(bb_starknet)");
+    if has_starknet_flavors(&barretenberg.lib_dirs) {
+        println!("cargo:rustc-cfg=bb_starknet");
+        println!("cargo:starknet=1");
+    } else {
+        println!(
+            "cargo:warning=barretenberg was built without STARKNET_GARAGA_FLAVORS, \
+             the Starknet flavor is unavailable"
+        );
+    }
+
+    // Exposed to dependents as DEP_BARRETENBERG_INCLUDE
+    let include_path = env::join_paths(&barretenberg.include_dirs).unwrap();
+    println!("cargo:include={}", include_path.to_string_lossy());
+
+    // Libraries are linked in the correct order (dependencies last) below
+    for lib_dir in &barretenberg.lib_dirs {
+        println!("cargo:rustc-link-search=native={}", lib_dir.display());
+    }
+
+    #[cfg(feature = "dynamic")]
+    link_dynamic(&barretenberg.lib_dirs);
+    #[cfg(not(feature = "dynamic"))]
+    link_static(&barretenberg.lib_dirs);
+
+    #[cfg(feature = "regenerate-bindings")]
+    regenerate_bindings(&barretenberg.include_dirs);
+}
+
+#[cfg(not(feature = "dynamic"))]
+fn link_static(lib_dirs: &[PathBuf]) {
+    // Main libraries FIRST (they depend on libdeflate)
+    println!("cargo:rustc-link-lib=static=barretenberg");
+    println!("cargo:rustc-link-lib=static=env");
+
+    // Then dependencies
+    // Search for libdeflate
+    if let Some(deflate_path) = find_library(lib_dirs, "libdeflate") {
+        println!("cargo:rustc-link-search=native={}", deflate_path.display());
+        println!("cargo:rustc-link-lib=static=deflate");
+    } else {
+        // Try system libdeflate
+        println!("cargo:warning=libdeflate not found next to barretenberg, trying system library");
+        println!("cargo:rustc-link-lib=deflate");
+    }
+
+    // System libraries
+    println!("cargo:rustc-link-lib=stdc++");
+    println!("cargo:rustc-link-lib=pthread");
+    println!("cargo:rustc-link-lib=m"); // math library
+
+    // On some systems, you might also need:
+    // println!("cargo:rustc-link-lib=gomp"); // OpenMP if used
+}
+
+/// Link the shared object, which carries its own dependencies
+///
+/// The rpath only covers this crate's own tests. Dependents read
+/// `DEP_BARRETENBERG_LIB_DIR` to set theirs, or rely on `LD_LIBRARY_PATH`.
+#[cfg(feature = "dynamic")]
+fn link_dynamic(lib_dirs: &[PathBuf]) {
+    println!("cargo:rustc-link-lib=dylib=barretenberg");
+
+    // barretenberg sets no SOVERSION; the runtime check compares build ids instead
+    let library = lib_dirs
+        .iter()
+        .map(|dir| dir.join(LIB_FILE))
+        .find(|path| path.is_file());
+    if let Some(library) = &library {
+        println!("cargo:rerun-if-changed={}", library.display());
+    }
+    let build_id = library.as_deref().and_then(elf_build_id);
+    if build_id.is_none() {
+        println!(
+            "cargo:warning={} has no GNU build id, check_loaded_version will always fail",
+            LIB_FILE
+        );
+    }
+    println!(
+        "cargo:rustc-env=BB_LINKED_BUILD_ID={}",
+        build_id.unwrap_or_default()
+    );
+
+    let lib_path = env::join_paths(lib_dirs).unwrap();
+    println!("cargo:lib_dir={}", lib_path.to_string_lossy());
+    if env::var_os("BB_NO_RPATH").is_none() {
+        for lib_dir in lib_dirs {
+            println!("cargo:rustc-link-arg=-Wl,-rpath,{}", lib_dir.display());
+        }
+    }
+}
+
+/// GNU build id of a 64-bit little-endian ELF shared object, as lowercase hex
+#[cfg(feature = "dynamic")]
+fn elf_build_id(path: &Path) -> Option<String> {
+    const PT_NOTE: u32 = 4;
+
+    let elf = std::fs::read(path).ok()?;
+    if elf.get(..6)? != b"\x7fELF\x02\x01" {
+        return None;
+    }
+    let u16_at = |at: usize| Some(u16::from_le_bytes(elf.get(at..at + 2)?.try_into().ok()?));
+    let u32_at = |at: usize| Some(u32::from_le_bytes(elf.get(at..at + 4)?.try_into().ok()?));
+    let u64_at = |at: usize| Some(u64::from_le_bytes(elf.get(at..at + 8)?.try_into().ok()?));
+
+    // e_phoff, e_phentsize and e_phnum; p_type, p_offset and p_filesz
+    let program_headers = u64_at(0x20)? as usize;
+    let header_size = u16_at(0x36)? as usize;
+    (0..u16_at(0x38)? as usize).find_map(|index| {
+        let header = program_headers + index * header_size;
+        if u32_at(header)? != PT_NOTE {
+            return None;
+        }
+        let offset = u64_at(header + 0x08)? as usize;
+        let size = u64_at(header + 0x20)? as usize;
+        build_id_note(elf.get(offset..offset + size)?)
+    })
+}
+
+/// The `NT_GNU_BUILD_ID` descriptor among ELF notes, as lowercase hex
+///
+/// Kept in step with `barretenberg::dynamic::build_id_note`, which reads the
+/// same notes from the loaded library.
+#[cfg(feature = "dynamic")]
+fn build_id_note(mut notes: &[u8]) -> Option<String> {
+    const NT_GNU_BUILD_ID: u32 = 3;
+    let align = |size: usize| (size + 3) & !3;
+
+    while notes.len() >= 12 {
+        let word = |at: usize| u32::from_ne_bytes(notes[at..at + 4].try_into().unwrap()) as usize;
+        let (name_size, desc_size, kind) = (word(0), word(4), word(8) as u32);
+        let desc_start = 12 + align(name_size);
+        let name = notes.get(12..12 + name_size)?;
+        let desc = notes.get(desc_start..desc_start + desc_size)?;
+        if kind == NT_GNU_BUILD_ID && name == b"GNU\0" {
+            return Some(desc.iter().map(|byte| format!("{:02x}", byte)).collect());
+        }
+        notes = notes.get(desc_start + align(desc_size)..)?;
+    }
+    None
+}
+
+/// Run bindgen against the barretenberg headers, writing `OUT_DIR/bindings.rs`
+///
+/// With `BB_UPDATE_BINDINGS` set the committed `src/bindings.rs` is
+/// overwritten as well.
+#[cfg(feature = "regenerate-bindings")]
+fn regenerate_bindings(include_dirs: &[PathBuf]) {
+    if include_dirs.is_empty() {
+        panic!("regenerate-bindings needs barretenberg headers, set BARRETENBERG_DIR or BB_INCLUDE_DIR");
+    }
+
+    let mut clang_args = vec!["-std=c++20".to_string(), "-xc++".to_string()];
+    for include_dir in include_dirs {
+        clang_args.push(format!("-I{}", include_dir.display()));
+    }
+    clang_args.push("-DTRACY_ENABLE=OFF".to_string());
+
+    let bindings = bindgen::Builder::default()
+        .header_contents(
+            "wrapper.hpp",
+            r#"
+                #include <barretenberg/crypto/pedersen_commitment/c_bind.hpp>
+                #include <barretenberg/crypto/pedersen_hash/c_bind.hpp>
+                #include <barretenberg/crypto/poseidon2/c_bind.hpp>
+                #include <barretenberg/crypto/blake2s/c_bind.hpp>
+                #include <barretenberg/crypto/schnorr/c_bind.hpp>
+                #include <barretenberg/srs/c_bind.hpp>
+                #include <barretenberg/common/c_bind.hpp>
+                #include <barretenberg/dsl/acir_proofs/c_bind.hpp>
+            "#,
+        )
+        // Add include paths
+        .clang_args(&clang_args)
+        // Block problematic types that we'll define manually
+        .blocklist_type("out_buf")
+        .blocklist_type("in_buf")
+        .blocklist_type("vec_in_buf")
+        .blocklist_type("out_buf32")
+        // Crypto functions
+        .allowlist_function("pedersen_commit")
+        .allowlist_function("pedersen_hash")
+        .allowlist_function("pedersen_hashes")
+        .allowlist_function("pedersen_hash_buffer")
+        .allowlist_function("poseidon2_hash")
+        .allowlist_function("poseidon2_hashes")
+        .allowlist_function("poseidon2_permutation")
+        .allowlist_function("poseidon2_hash_accumulate")
+        .allowlist_function("blake2s")
+        .allowlist_function("blake2s_to_field_")
+        // Schnorr signatures
+        .allowlist_function("schnorr_construct_signature")
+        .allowlist_function("schnorr_verify_signature")
+        .allowlist_function("schnorr_multisig_create_multisig_public_key")
+        .allowlist_function("schnorr_multisig_validate_and_combine_signer_pubkeys")
+        .allowlist_function("schnorr_multisig_construct_signature_round_1")
+        .allowlist_function("schnorr_multisig_construct_signature_round_2")
+        .allowlist_function("schnorr_multisig_combine_signatures")
+        // SRS and common
+        .allowlist_function("srs_init_srs")
+        .allowlist_function("srs_init_grumpkin_srs")
+        .allowlist_function("test_threads")
+        .allowlist_function("common_init_slab_allocator")
+        // ACIR functions
+        .allowlist_function("acir_get_circuit_sizes")
+        .allowlist_function("acir_load_verification_key")
+        .allowlist_function("acir_init_verification_key")
+        .allowlist_function("acir_get_verification_key")
+        .allowlist_function("acir_get_proving_key")
+        .allowlist_function("acir_verify_proof")
+        .allowlist_function("acir_get_solidity_verifier")
+        .allowlist_function("acir_serialize_proof_into_fields")
+        .allowlist_function("acir_serialize_verification_key_into_fields")
+        // Ultra Honk functions
+        .allowlist_function("acir_prove_ultra_honk")
+        .allowlist_function("acir_prove_ultra_keccak_honk")
+        .allowlist_function("acir_verify_ultra_honk")
+        .allowlist_function("acir_write_vk_ultra_honk")
+        .allowlist_function("acir_prove_and_verify_ultra_honk")
+        .allowlist_function("acir_proof_as_fields_ultra_honk")
+        // Use correct layout and derive Debug where possible
+        .layout_tests(false)
+        .derive_debug(true)
+        .derive_default(true)
+        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
+        .generate()
+        .expect("Unable to generate bindings");
+
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+    bindings
+        .write_to_file(out_path.join("bindings.rs"))
+        .expect("Couldn't write bindings!");
+
+    if env::var_os("BB_UPDATE_BINDINGS").is_some() {
+        bindings
+            .write_to_file("src/bindings.rs")
+            .expect("Couldn't update src/bindings.rs!");
+        println!("cargo:warning=Updated src/bindings.rs");
+    }
+
+    println!("cargo:warning=Bindings generated successfully!");
+}
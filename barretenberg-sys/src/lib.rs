@@ -0,0 +1,53 @@
+//! Raw FFI bindings to barretenberg's C API
+//!
+//! Every function follows barretenberg's `c_bind` conventions: integers are
+//! big-endian, variable-length inputs are length-prefixed, and `*mut *mut u8` /
+//! `vec_out_buf` results are heap buffers that must be released with [`bbfree`].
+//! Safe wrappers live in `barretenberg-rust-bindings`.
+
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+
+// Aliases required by bindings.rs:
+pub type out_buf = *mut u8;
+pub type in_buf = *const u8;
+pub type vec_in_buf = *const u8;
+pub type out_buf32 = *mut u8;
+
+/// Version of the barretenberg found at build time, `"unknown"` if undetermined
+pub const LINKED_BB_VERSION: &str = env!("BB_LINKED_VERSION");
+/// Source commit of the barretenberg found at build time, `"unknown"` if undetermined
+pub const LINKED_BB_GIT_HASH: &str = env!("BB_LINKED_GIT_HASH");
+/// GNU build id of the shared library linked against, empty if it has none
+#[cfg(feature = "dynamic")]
+pub const LINKED_BB_BUILD_ID: &str = env!("BB_LINKED_BUILD_ID");
+
+/// Version of the barretenberg headers the bindings were generated from
+#[cfg(not(feature = "regenerate-bindings"))]
+pub const BINDINGS_BB_VERSION: &str = "0.87.0";
+#[cfg(feature = "regenerate-bindings")]
+pub const BINDINGS_BB_VERSION: &str = LINKED_BB_VERSION;
+
+// Committed bindings by default, so building needs neither libclang nor the headers
+#[cfg(not(feature = "regenerate-bindings"))]
+include!("bindings.rs");
+#[cfg(feature = "regenerate-bindings")]
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+mod manual;
+pub use manual::*;
+
+#[cfg(all(test, feature = "regenerate-bindings"))]
+mod tests {
+    #[test]
+    fn committed_bindings_match_bindgen() {
+        let committed = include_str!("bindings.rs");
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+        assert!(
+            committed == generated,
+            "src/bindings.rs is out of date, rebuild with \
+             `BB_UPDATE_BINDINGS=1 cargo build --features regenerate-bindings`"
+        );
+    }
+}
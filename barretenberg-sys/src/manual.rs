@@ -0,0 +1,67 @@
+//! Entry points declared by hand
+//!
+//! The committed `bindings.rs` is bindgen's output for the v0.87.0 headers, so
+//! these are not covered by `committed_bindings_match_bindgen`. They are kept
+//! out of the bindgen allowlist so regenerating never declares them twice;
+//! check their signatures against the headers when upgrading barretenberg.
+//!
+//! The Starknet entry points only exist when barretenberg was built with
+//! `STARKNET_GARAGA_FLAVORS`, which the build script detects as `bb_starknet`.
+
+use crate::{in_buf, out_buf};
+
+unsafe extern "C" {
+    /// Release a buffer barretenberg allocated for an output argument
+    pub fn bbfree(ptr: *mut ::std::os::raw::c_void);
+}
+unsafe extern "C" {
+    pub fn schnorr_compute_public_key(private_key: in_buf, public_key_buf: out_buf);
+}
+unsafe extern "C" {
+    pub fn aes_encrypt_buffer_cbc(
+        input: *const u8,
+        iv: *const u8,
+        key: *const u8,
+        length: *const u32,
+        r: *mut *mut u8,
+    );
+}
+unsafe extern "C" {
+    pub fn aes_decrypt_buffer_cbc(
+        input: *const u8,
+        iv: *const u8,
+        key: *const u8,
+        length: *const u32,
+        r: *mut *mut u8,
+    );
+}
+unsafe extern "C" {
+    pub fn acir_verify_ultra_keccak_honk(
+        proof_buf: *const u8,
+        vk_buf: *const u8,
+        result: *mut bool,
+    );
+}
+unsafe extern "C" {
+    pub fn acir_write_vk_ultra_keccak_honk(acir_vec: *const u8, out: *mut *mut u8);
+}
+#[cfg(bb_starknet)]
+unsafe extern "C" {
+    pub fn acir_prove_ultra_starknet_honk(
+        acir_vec: *const u8,
+        witness_vec: *const u8,
+        out: *mut *mut u8,
+    );
+}
+#[cfg(bb_starknet)]
+unsafe extern "C" {
+    pub fn acir_verify_ultra_starknet_honk(
+        proof_buf: *const u8,
+        vk_buf: *const u8,
+        result: *mut bool,
+    );
+}
+#[cfg(bb_starknet)]
+unsafe extern "C" {
+    pub fn acir_write_vk_ultra_starknet_honk(acir_vec: *const u8, out: *mut *mut u8);
+}
@@ -5,14 +5,20 @@
 
 set -e  # Exit on any error
 
-BARRETENBERG_PATH="$HOME/dev/visoft/aztec/aztec-packages/barretenberg/cpp"
+if [ -z "$BARRETENBERG_PATH" ]; then
+    echo "Usage: BARRETENBERG_PATH=/path/to/aztec-packages/barretenberg/cpp $0 [--clean]"
+    echo ""
+    echo "  BARRETENBERG_PATH  barretenberg/cpp checkout to build (required)"
+    echo "  --clean            remove the existing build directory first"
+    exit 1
+fi
 
 echo "🔨 Building Barretenberg with Rust-friendly flags..."
 
 # Check if dir is present
 if [ ! -d "$BARRETENBERG_PATH" ]; then
     echo "❌ Error: Barretenberg path not found: $BARRETENBERG_PATH"
-    echo "Please update BARRETENBERG_PATH in this script"
+    echo "Please set BARRETENBERG_PATH to your barretenberg/cpp checkout"
     exit 1
 fi
 
@@ -26,8 +32,8 @@ if [ "$1" = "--clean" ]; then
     rm -rf build
 fi
 
-echo "⚙️  Configuring CMake with Tracy disabled..."
-cmake --preset=default -DTRACY_ENABLE=OFF
+echo "⚙️  Configuring CMake with Tracy disabled and Starknet flavors enabled..."
+cmake --preset=default -DTRACY_ENABLE=OFF -DSTARKNET_GARAGA_FLAVORS=ON
 
 echo "🔨 Building bb target..."
 cmake --build build --target bb
@@ -50,4 +56,4 @@ fi
 
 echo ""
 echo "🚀 Ready to build Rust bindings with:"
-echo "   cargo build"
\ No newline at end of file
+echo "   BARRETENBERG_DIR=$BARRETENBERG_PATH cargo build"
\ No newline at end of file
@@ -0,0 +1,292 @@
+//! Conversion between named, typed circuit inputs and flat witness values
+//!
+//! Encoding is `noirc_abi`'s: parameters are flattened in ABI order into
+//! consecutive witnesses starting at `Witness(0)`, struct fields in
+//! declaration order, strings one byte per field and signed integers in two's
+//! complement.
+
+use std::collections::BTreeMap;
+
+use acir::{
+    native_types::{Witness, WitnessMap},
+    FieldElement,
+};
+use noirc_abi::{errors::AbiError, Abi, AbiParameter, AbiReturnType, AbiVisibility};
+
+pub use noirc_abi::input_parser::InputValue;
+
+use crate::{error::BbError, fr::Fr};
+
+/// Name the return value is decoded under
+const RETURN_NAME: &str = "return";
+
+/// Named inputs, keyed by parameter name
+pub type InputMap = BTreeMap<String, InputValue>;
+
+pub(crate) fn invalid_input(path: &str, message: impl Into<String>) -> BbError {
+    BbError::InvalidInput {
+        path: path.to_string(),
+        line: None,
+        message: message.into(),
+    }
+}
+
+/// Map a `noirc_abi` error to the parameter it names
+fn abi_error(error: AbiError) -> BbError {
+    let path = match &error {
+        AbiError::UnexpectedParams(names) => names.first().cloned().unwrap_or_default(),
+        AbiError::TypeMismatch { param, .. } => param.name.clone(),
+        AbiError::MissingParam(name) => name.clone(),
+        _ => "return".to_string(),
+    };
+    invalid_input(&path, error.to_string())
+}
+
+/// Encode named inputs into the initial witness map of a circuit
+///
+/// Delegates to `noirc_abi::Abi::encode`, so values follow its conventions:
+/// signed integers are given in two's complement (`255` for an `i8` of -1),
+/// as `noirc_abi`'s own input parsers and `inputs::parse_inputs` produce them.
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit, e.g. `NoirArtifact::abi`
+/// * inputs: A value for every parameter, keyed by name
+///
+/// # Returns
+///
+/// The witness map, or `BbError::InvalidInput` naming the offending parameter
+pub fn encode_inputs(abi: &Abi, inputs: &InputMap) -> Result<WitnessMap<FieldElement>, BbError> {
+    abi.encode(inputs, None).map_err(abi_error)
+}
+
+fn public_parameters(abi: &Abi) -> impl Iterator<Item = &AbiParameter> {
+    abi.parameters
+        .iter()
+        .filter(|parameter| parameter.visibility == AbiVisibility::Public)
+}
+
+fn public_return_type(abi: &Abi) -> Option<&AbiReturnType> {
+    abi.return_type
+        .as_ref()
+        .filter(|return_type| return_type.visibility == AbiVisibility::Public)
+}
+
+/// Number of field elements a proof exposes as public inputs
+///
+/// Public parameters followed by a public return value, as laid out in the ABI.
+pub fn num_public_inputs(abi: &Abi) -> usize {
+    let parameters: u32 = public_parameters(abi)
+        .map(|parameter| parameter.typ.field_count())
+        .sum();
+    let return_value =
+        public_return_type(abi).map_or(0, |return_type| return_type.abi_type.field_count());
+    (parameters + return_value) as usize
+}
+
+/// Decode `fields` as the consecutive values of `parameters` with `noirc_abi::Abi::decode`
+fn decode_parameters(parameters: Vec<AbiParameter>, fields: &[Fr]) -> Result<InputMap, BbError> {
+    let abi = Abi {
+        parameters,
+        return_type: None,
+        error_types: BTreeMap::new(),
+    };
+    let witness_map: WitnessMap<FieldElement> = fields
+        .iter()
+        .enumerate()
+        .map(|(index, &fr)| (Witness(index as u32), FieldElement::from(fr)))
+        .collect::<BTreeMap<_, _>>()
+        .into();
+    abi.decode(&witness_map)
+        .map(|(inputs, _)| inputs)
+        .map_err(abi_error)
+}
+
+fn check_public_input_count(abi: &Abi, public_inputs: &[Fr]) -> Result<(), BbError> {
+    let expected = num_public_inputs(abi);
+    if public_inputs.len() != expected {
+        return Err(BbError::PublicInputsMismatch {
+            expected,
+            actual: public_inputs.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Map the flat public inputs of a proof back to the named public parameters
+///
+/// Decoded by `noirc_abi::Abi::decode`, so signed integers come back in two's complement.
+///
+/// # Arguments
+///
+/// * abi: The ABI of the proven circuit
+/// * public_inputs: All public inputs of the proof, e.g. `ProofResponse::public_inputs_fr`
+///
+/// # Returns
+///
+/// The value of every public parameter, keyed by name
+pub fn decode_public_inputs(abi: &Abi, public_inputs: &[Fr]) -> Result<InputMap, BbError> {
+    check_public_input_count(abi, public_inputs)?;
+    decode_parameters(public_parameters(abi).cloned().collect(), public_inputs)
+}
+
+/// Decode the public return value from the public inputs of a proof
+///
+/// # Arguments
+///
+/// * abi: The ABI of the proven circuit
+/// * public_inputs: All public inputs of the proof, e.g. `ProofResponse::public_inputs_fr`
+///
+/// # Returns
+///
+/// The return value, or `None` if the circuit does not publicly return one
+pub fn decode_return_value(abi: &Abi, public_inputs: &[Fr]) -> Result<Option<InputValue>, BbError> {
+    check_public_input_count(abi, public_inputs)?;
+    let Some(return_type) = public_return_type(abi) else {
+        return Ok(None);
+    };
+    let skip = public_inputs.len() - return_type.abi_type.field_count() as usize;
+    let parameter = AbiParameter {
+        name: RETURN_NAME.to_string(),
+        typ: return_type.abi_type.clone(),
+        visibility: AbiVisibility::Public,
+    };
+    let mut decoded = decode_parameters(vec![parameter], &public_inputs[skip..])?;
+    Ok(decoded.remove(RETURN_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use acir::AcirField;
+    use noirc_abi::{AbiType, Sign};
+
+    use super::*;
+
+    fn parameter(name: &str, typ: AbiType) -> AbiParameter {
+        AbiParameter {
+            name: name.to_string(),
+            typ,
+            visibility: AbiVisibility::Private,
+        }
+    }
+
+    fn abi() -> Abi {
+        Abi {
+            parameters: vec![
+                parameter(
+                    "point",
+                    AbiType::Struct {
+                        path: "Point".to_string(),
+                        fields: vec![
+                            ("x".to_string(), AbiType::Field),
+                            (
+                                "y".to_string(),
+                                AbiType::Integer {
+                                    sign: Sign::Signed,
+                                    width: 8,
+                                },
+                            ),
+                        ],
+                    },
+                ),
+                parameter(
+                    "flags",
+                    AbiType::Array {
+                        length: 2,
+                        typ: Box::new(AbiType::Boolean),
+                    },
+                ),
+                parameter("name", AbiType::String { length: 2 }),
+            ],
+            return_type: None,
+            error_types: BTreeMap::new(),
+        }
+    }
+
+    fn field(value: u128) -> InputValue {
+        InputValue::Field(FieldElement::from(value))
+    }
+
+    /// `y` in two's complement, e.g. 255 for -1
+    fn inputs(y: u128, flag: u128) -> InputMap {
+        BTreeMap::from([
+            (
+                "point".to_string(),
+                InputValue::Struct(BTreeMap::from([
+                    ("x".to_string(), field(7)),
+                    ("y".to_string(), field(y)),
+                ])),
+            ),
+            (
+                "flags".to_string(),
+                InputValue::Vec(vec![field(1), field(flag)]),
+            ),
+            ("name".to_string(), InputValue::String("hi".to_string())),
+        ])
+    }
+
+    #[test]
+    fn flattens_in_abi_order() {
+        let witness = encode_inputs(&abi(), &inputs(255, 0)).unwrap();
+        let values: Vec<u128> = (0..6)
+            .map(|index| witness[&Witness(index)].to_u128())
+            .collect();
+        assert_eq!(values, vec![7, 255, 1, 0, b'h' as u128, b'i' as u128]);
+    }
+
+    #[test]
+    fn reports_parameter_path() {
+        let mut mismatched = inputs(0, 0);
+        mismatched.insert("flags".to_string(), field(1));
+        let err = encode_inputs(&abi(), &mismatched).unwrap_err();
+        assert!(matches!(err, BbError::InvalidInput { ref path, .. } if path == "flags"));
+
+        let mut missing = inputs(0, 0);
+        missing.remove("name");
+        let err = encode_inputs(&abi(), &missing).unwrap_err();
+        assert!(matches!(err, BbError::InvalidInput { ref path, .. } if path == "name"));
+
+        let mut unknown = inputs(0, 0);
+        unknown.insert("z".to_string(), field(0));
+        let err = encode_inputs(&abi(), &unknown).unwrap_err();
+        assert!(matches!(err, BbError::InvalidInput { ref path, .. } if path == "z"));
+    }
+
+    #[test]
+    fn decodes_public_inputs_and_return_value() {
+        let mut abi = abi();
+        for parameter in &mut abi.parameters {
+            parameter.visibility = AbiVisibility::Public;
+        }
+        abi.return_type = Some(AbiReturnType {
+            abi_type: AbiType::Integer {
+                sign: Sign::Signed,
+                width: 8,
+            },
+            visibility: AbiVisibility::Public,
+        });
+
+        let witness = encode_inputs(&abi, &inputs(255, 0)).unwrap();
+        let mut public_inputs: Vec<Fr> = witness
+            .into_iter()
+            .map(|(_, value)| Fr::from(value))
+            .collect();
+        public_inputs.push(Fr::from(200u64));
+
+        let decoded = decode_public_inputs(&abi, &public_inputs).unwrap();
+        assert_eq!(decoded, inputs(255, 0));
+        assert_eq!(
+            decode_return_value(&abi, &public_inputs).unwrap(),
+            Some(field(200))
+        );
+
+        public_inputs.pop();
+        assert!(matches!(
+            decode_public_inputs(&abi, &public_inputs),
+            Err(BbError::PublicInputsMismatch {
+                expected: 7,
+                actual: 6
+            })
+        ));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{bindgen, buffer::BbBuffer, error::BbError};
+
+const BLOCK_SIZE: usize = 16;
+
+/// Apply PKCS#7 padding, always adding between 1 and 16 bytes
+fn pad(data: &[u8]) -> Vec<u8> {
+    let padding = BLOCK_SIZE - data.len() % BLOCK_SIZE;
+    let mut padded = Vec::with_capacity(data.len() + padding);
+    padded.extend_from_slice(data);
+    padded.resize(data.len() + padding, padding as u8);
+    padded
+}
+
+/// Strip and check PKCS#7 padding
+fn unpad(mut data: Vec<u8>) -> Result<Vec<u8>, BbError> {
+    let padding = *data
+        .last()
+        .ok_or_else(|| BbError::Aes("empty plaintext has no padding".to_string()))?
+        as usize;
+    if padding == 0
+        || padding > BLOCK_SIZE
+        || padding > data.len()
+        || data[data.len() - padding..]
+            .iter()
+            .any(|&b| b as usize != padding)
+    {
+        return Err(BbError::Aes("invalid PKCS#7 padding".to_string()));
+    }
+    data.truncate(data.len() - padding);
+    Ok(data)
+}
+
+/// Run barretenberg's in-place CBC routine over a copy of `input`
+fn run_cbc(
+    f: unsafe extern "C" fn(*const u8, *const u8, *const u8, *const u32, *mut *mut u8),
+    key: &[u8; 16],
+    iv: &[u8; 16],
+    input: &[u8],
+) -> Result<Vec<u8>, BbError> {
+    // barretenberg overwrites both the input and the IV
+    let mut input = input.to_vec();
+    let mut iv = *iv;
+    let length = (input.len() as u32).to_be();
+    let out = unsafe {
+        BbBuffer::from_out_ptr(|out| {
+            f(
+                input.as_mut_ptr() as *const u8,
+                iv.as_mut_ptr() as *const u8,
+                key.as_ptr(),
+                &length,
+                out,
+            )
+        })?
+    };
+    Ok(out.as_bytes().to_vec())
+}
+
+/// AES-128-CBC encryption with PKCS#7 padding
+///
+/// Matches the output of Noir's `std::aes128::aes128_encrypt` black box.
+///
+/// # Arguments
+///
+/// * key: The 128-bit key
+/// * iv: The initialization vector
+/// * plaintext: The data to encrypt, of any length
+///
+/// # Returns
+///
+/// The ciphertext, a non-empty multiple of 16 bytes
+pub fn encrypt_cbc(key: &[u8; 16], iv: &[u8; 16], plaintext: &[u8]) -> Result<Vec<u8>, BbError> {
+    run_cbc(bindgen::aes_encrypt_buffer_cbc, key, iv, &pad(plaintext))
+}
+
+/// AES-128-CBC decryption, removing PKCS#7 padding
+///
+/// # Arguments
+///
+/// * key: The 128-bit key
+/// * iv: The initialization vector
+/// * ciphertext: The data to decrypt, a non-empty multiple of 16 bytes
+///
+/// # Returns
+///
+/// The plaintext
+pub fn decrypt_cbc(key: &[u8; 16], iv: &[u8; 16], ciphertext: &[u8]) -> Result<Vec<u8>, BbError> {
+    if ciphertext.is_empty() || ciphertext.len() % BLOCK_SIZE != 0 {
+        return Err(BbError::Aes(format!(
+            "ciphertext length {} is not a positive multiple of {}",
+            ciphertext.len(),
+            BLOCK_SIZE
+        )));
+    }
+    unpad(run_cbc(
+        bindgen::aes_decrypt_buffer_cbc,
+        key,
+        iv,
+        ciphertext,
+    )?)
+}
@@ -0,0 +1,406 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use acir::{
+    circuit::{brillig::BrilligFunctionId, OpcodeLocation, Program, ResolvedOpcodeLocation},
+    native_types::{WitnessMap, WitnessStack},
+    FieldElement,
+};
+use nargo::foreign_calls::{
+    default::DefaultForeignCallBuilder, print::PrintOutput, ForeignCallExecutor,
+};
+use noirc_abi::Abi;
+use noirc_errors::debug_info::ProgramDebugInfo;
+use serde::{de, Deserialize, Deserializer};
+
+use crate::{
+    abi::num_public_inputs,
+    circuits::{decode_circuit, get_program},
+    error::BbError,
+    execute::{execute_program_with, SourceLocation},
+    flavor::OracleHashFlavor,
+    prove::{prove_ultra_honk, ProofResponse},
+};
+
+/// A source file referenced by the debug symbols
+#[derive(Debug, Clone, Deserialize)]
+pub struct DebugFile {
+    pub source: String,
+    pub path: PathBuf,
+}
+
+/// Compiled Noir program as written by `nargo compile` to `target/<name>.json`
+#[derive(Debug, Clone, Deserialize)]
+pub struct NoirArtifact {
+    pub noir_version: String,
+    /// Hash of the program, as reported by nargo
+    #[serde(deserialize_with = "deserialize_hash")]
+    pub hash: u64,
+    pub abi: Abi,
+    /// Base64-encoded, gzipped ACIR program
+    pub bytecode: String,
+    #[serde(
+        default,
+        deserialize_with = "ProgramDebugInfo::deserialize_compressed_base64_json"
+    )]
+    pub debug_symbols: ProgramDebugInfo,
+    /// Source files of the debug symbols, keyed by file id
+    #[serde(default)]
+    pub file_map: BTreeMap<u32, DebugFile>,
+}
+
+/// nargo writes the hash as a number, older versions as a decimal string
+fn deserialize_hash<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Hash {
+        Number(u64),
+        String(String),
+    }
+
+    match Hash::deserialize(deserializer)? {
+        Hash::Number(hash) => Ok(hash),
+        Hash::String(hash) => hash.parse().map_err(de::Error::custom),
+    }
+}
+
+impl NoirArtifact {
+    /// Parse an artifact from its JSON text
+    pub fn from_json(json: &str) -> Result<Self, BbError> {
+        serde_json::from_str(json).map_err(|e| BbError::Artifact(e.to_string()))
+    }
+
+    /// Read an artifact from a file, usually `target/<name>.json`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, BbError> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .map_err(|e| BbError::Artifact(format!("{}: {}", path.display(), e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| BbError::Artifact(format!("{}: {}", path.display(), e)))
+    }
+
+    /// The deserialized ACIR program
+    pub fn program(&self) -> Result<Program<FieldElement>, BbError> {
+        get_program(&self.bytecode)
+    }
+
+    /// Compressed and uncompressed ACIR buffers, as `circuits::decode_circuit`
+    pub fn decode_circuit(&self) -> Result<(Vec<u8>, Vec<u8>), BbError> {
+        decode_circuit(&self.bytecode)
+    }
+
+    /// Number of field elements the proof exposes as public inputs
+    pub fn num_public_inputs(&self) -> usize {
+        num_public_inputs(&self.abi)
+    }
+
+    /// Execute the program, sending `print` output to `print_output`; failures
+    /// carry decoded assertion messages and source locations from the debug symbols
+    pub fn execute(
+        &self,
+        initial_witness: WitnessMap<FieldElement>,
+        print_output: PrintOutput<'_>,
+    ) -> Result<WitnessStack<FieldElement>, BbError> {
+        let mut foreign_call_executor = DefaultForeignCallBuilder::default()
+            .with_output(print_output)
+            .build();
+        self.execute_with_oracles(initial_witness, &mut foreign_call_executor)
+    }
+
+    /// Execute the program, answering foreign calls with a custom executor
+    pub fn execute_with_oracles<E: ForeignCallExecutor<FieldElement>>(
+        &self,
+        initial_witness: WitnessMap<FieldElement>,
+        foreign_call_executor: &mut E,
+    ) -> Result<WitnessStack<FieldElement>, BbError> {
+        execute_program_with(
+            &self.program()?,
+            initial_witness,
+            foreign_call_executor,
+            Some(self),
+        )
+    }
+
+    /// Noir source locations of an opcode, including inlined calls, outermost first
+    ///
+    /// Opcodes inside unconstrained functions are resolved with the debug
+    /// symbols of `brillig_function_id`, the function that was running. Without
+    /// it they resolve to the call site of the unconstrained function.
+    pub fn source_locations(
+        &self,
+        location: &ResolvedOpcodeLocation,
+        brillig_function_id: Option<BrilligFunctionId>,
+    ) -> Vec<SourceLocation> {
+        let Some(debug_info) = self
+            .debug_symbols
+            .debug_infos
+            .get(location.acir_function_index)
+        else {
+            return Vec::new();
+        };
+        let locations = match location.opcode_location {
+            OpcodeLocation::Acir(_) => debug_info.opcode_location(&location.opcode_location),
+            OpcodeLocation::Brillig { acir_index, .. } => brillig_function_id
+                .zip(location.opcode_location.to_brillig_location())
+                .and_then(|(function_id, brillig_location)| {
+                    debug_info
+                        .brillig_locations
+                        .get(&function_id)?
+                        .get(&brillig_location)
+                })
+                .map(|call_stack| debug_info.location_tree.get_call_stack(*call_stack))
+                .or_else(|| debug_info.opcode_location(&OpcodeLocation::Acir(acir_index))),
+        }
+        .unwrap_or_default();
+
+        locations
+            .into_iter()
+            .filter_map(|location| {
+                let file = self.file_map.get(&(location.file.as_usize() as u32))?;
+                Some(SourceLocation::new(
+                    file.path.clone(),
+                    &file.source,
+                    location.span.start() as usize,
+                ))
+            })
+            .collect()
+    }
+
+    /// Execute and prove the program, checking the public input count against the ABI
+    pub fn prove_ultra_honk(
+        &self,
+        initial_witness: WitnessMap<FieldElement>,
+        flavor: OracleHashFlavor,
+        print_output: PrintOutput<'_>,
+    ) -> Result<ProofResponse, BbError> {
+        prove_ultra_honk(
+            &self.bytecode,
+            initial_witness,
+            Some(self.num_public_inputs()),
+            flavor,
+            print_output,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acir::{
+        circuit::{AcirOpcodeLocation, AssertionPayload, Circuit, ErrorSelector, Opcode},
+        native_types::{Expression, Witness},
+        AcirField,
+    };
+    use base64::engine::{general_purpose, Engine};
+    use noirc_abi::{AbiErrorType, AbiParameter, AbiType, AbiVisibility};
+    use noirc_errors::{
+        call_stack::{CallStackId, LocationNodeDebugInfo, LocationTree},
+        debug_info::DebugInfo,
+        Location, Span,
+    };
+
+    use super::*;
+    use crate::execute::ExecutionError;
+
+    const MAIN_NR: &str =
+        "fn main(x: Field, y: Field) {\n    assert(x == y, \"x and y differ\");\n}\n";
+    const ERROR_SELECTOR: u64 = 42;
+
+    fn artifact_json(hash: &str) -> String {
+        format!(
+            r#"{{
+                "noir_version": "1.0.0-beta.4",
+                "hash": {},
+                "abi": {{
+                    "parameters": [
+                        {{ "name": "x", "type": {{ "kind": "field" }}, "visibility": "private" }},
+                        {{ "name": "y", "type": {{ "kind": "array", "length": 2, "type": {{ "kind": "field" }} }}, "visibility": "public" }}
+                    ],
+                    "return_type": {{ "abi_type": {{ "kind": "boolean" }}, "visibility": "public" }},
+                    "error_types": {{}}
+                }},
+                "bytecode": ""
+            }}"#,
+            hash
+        )
+    }
+
+    #[test]
+    fn accepts_numeric_and_string_hash() {
+        let numeric = NoirArtifact::from_json(&artifact_json("42")).unwrap();
+        let string = NoirArtifact::from_json(&artifact_json("\"42\"")).unwrap();
+        assert_eq!(numeric.hash, 42);
+        assert_eq!(string.hash, 42);
+        assert!(NoirArtifact::from_json(&artifact_json("\"0xzz\"")).is_err());
+    }
+
+    #[test]
+    fn counts_public_inputs() {
+        let artifact = NoirArtifact::from_json(&artifact_json("0")).unwrap();
+        assert_eq!(artifact.num_public_inputs(), 3);
+    }
+
+    /// Add the byte `offset` of `MAIN_NR` under `parent`, returning its call stack
+    fn push_location(tree: &mut LocationTree, parent: CallStackId, offset: usize) -> CallStackId {
+        tree.locations.push(LocationNodeDebugInfo {
+            parent: Some(parent),
+            value: Location::new(
+                Span::from(offset as u32..offset as u32 + 1),
+                Location::dummy().file,
+            ),
+        });
+        CallStackId::new(tree.locations.len() - 1)
+    }
+
+    /// What nargo compiles `MAIN_NR` to: one `x - y == 0` constraint carrying
+    /// the string assertion, with debug symbols pointing at the `assert`
+    fn assert_eq_artifact() -> NoirArtifact {
+        let circuit = Circuit {
+            current_witness_index: 1,
+            opcodes: vec![Opcode::AssertZero(Expression {
+                mul_terms: Vec::new(),
+                linear_combinations: vec![
+                    (FieldElement::one(), Witness(0)),
+                    (-FieldElement::one(), Witness(1)),
+                ],
+                q_c: FieldElement::zero(),
+            })],
+            private_parameters: [Witness(0), Witness(1)].into(),
+            assert_messages: vec![(
+                OpcodeLocation::Acir(0),
+                AssertionPayload {
+                    error_selector: ERROR_SELECTOR,
+                    payload: Vec::new(),
+                },
+            )],
+            ..Circuit::default()
+        };
+        let program = Program {
+            functions: vec![circuit],
+            unconstrained_functions: Vec::new(),
+        };
+
+        let mut location_tree = LocationTree {
+            locations: vec![LocationNodeDebugInfo {
+                parent: None,
+                value: Location::dummy(),
+            }],
+        };
+        let assert_call = push_location(
+            &mut location_tree,
+            CallStackId::root(),
+            MAIN_NR.find("assert").unwrap(),
+        );
+        let debug_info = DebugInfo {
+            acir_locations: [(AcirOpcodeLocation::new(0), assert_call)].into(),
+            location_tree,
+            ..DebugInfo::default()
+        };
+
+        let field = |name: &str| AbiParameter {
+            name: name.to_string(),
+            typ: AbiType::Field,
+            visibility: AbiVisibility::Private,
+        };
+        NoirArtifact {
+            noir_version: "1.0.0-beta.4".to_string(),
+            hash: 0,
+            abi: Abi {
+                parameters: vec![field("x"), field("y")],
+                return_type: None,
+                error_types: [(
+                    ErrorSelector::new(ERROR_SELECTOR),
+                    AbiErrorType::String {
+                        string: "x and y differ".to_string(),
+                    },
+                )]
+                .into(),
+            },
+            bytecode: general_purpose::STANDARD.encode(Program::serialize_program(&program)),
+            debug_symbols: ProgramDebugInfo {
+                debug_infos: vec![debug_info],
+            },
+            file_map: [(
+                Location::dummy().file.as_usize() as u32,
+                DebugFile {
+                    source: MAIN_NR.to_string(),
+                    path: PathBuf::from("src/main.nr"),
+                },
+            )]
+            .into(),
+        }
+    }
+
+    fn execution_error(result: Result<WitnessStack<FieldElement>, BbError>) -> ExecutionError {
+        match result {
+            Err(BbError::Execution(error)) => *error,
+            other => panic!("expected an execution error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn failed_assertion_has_message_and_location() {
+        let artifact = assert_eq_artifact();
+        let witness = WitnessMap::from(
+            [
+                (Witness(0), FieldElement::from(5u128)),
+                (Witness(1), FieldElement::from(6u128)),
+            ]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>(),
+        );
+
+        let error = execution_error(artifact.execute(witness, PrintOutput::None));
+        assert_eq!(error.assertion_message.as_deref(), Some("x and y differ"));
+        assert_eq!(
+            error
+                .source_locations
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["src/main.nr:2:5"]
+        );
+        assert!(error.to_string().ends_with("at src/main.nr:2:5"));
+    }
+
+    #[test]
+    fn resolves_brillig_frames() {
+        let mut artifact = assert_eq_artifact();
+        let debug_info = &mut artifact.debug_symbols.debug_infos[0];
+        let inner_assert = push_location(
+            &mut debug_info.location_tree,
+            CallStackId::root(),
+            MAIN_NR.find("x ==").unwrap(),
+        );
+        let brillig_location = OpcodeLocation::Brillig {
+            acir_index: 0,
+            brillig_index: 3,
+        };
+        debug_info.brillig_locations.insert(
+            BrilligFunctionId(0),
+            [(
+                brillig_location.to_brillig_location().unwrap(),
+                inner_assert,
+            )]
+            .into(),
+        );
+        let location = ResolvedOpcodeLocation {
+            acir_function_index: 0,
+            opcode_location: brillig_location,
+        };
+
+        let resolved = |function_id| {
+            artifact
+                .source_locations(&location, function_id)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            resolved(Some(BrilligFunctionId(0))),
+            vec!["src/main.nr:2:12"]
+        );
+        // Without the function id only the call site is known
+        assert_eq!(resolved(None), vec!["src/main.nr:2:5"]);
+    }
+}
@@ -0,0 +1,145 @@
+//! Runtime checks for a dynamically linked barretenberg
+
+use std::ffi::{c_int, c_void, CStr, OsStr};
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::slice;
+
+use crate::{bindgen, error::BbError};
+
+/// Path of the shared object `bbfree` was resolved from
+pub fn loaded_library_path() -> Option<PathBuf> {
+    let mut info: libc::Dl_info = unsafe { std::mem::zeroed() };
+    let symbol = bindgen::bbfree as *const c_void;
+    if unsafe { libc::dladdr(symbol, &mut info) } == 0 || info.dli_fname.is_null() {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr(info.dli_fname) };
+    Some(PathBuf::from(OsStr::from_bytes(name.to_bytes())))
+}
+
+/// The `NT_GNU_BUILD_ID` descriptor among ELF notes, as lowercase hex
+fn build_id_note(mut notes: &[u8]) -> Option<String> {
+    const NT_GNU_BUILD_ID: u32 = 3;
+    let align = |size: usize| (size + 3) & !3;
+
+    while notes.len() >= 12 {
+        let word = |at: usize| u32::from_ne_bytes(notes[at..at + 4].try_into().unwrap()) as usize;
+        let (name_size, desc_size, kind) = (word(0), word(4), word(8) as u32);
+        let desc_start = 12 + align(name_size);
+        let name = notes.get(12..12 + name_size)?;
+        let desc = notes.get(desc_start..desc_start + desc_size)?;
+        if kind == NT_GNU_BUILD_ID && name == b"GNU\0" {
+            return Some(desc.iter().map(|byte| format!("{:02x}", byte)).collect());
+        }
+        notes = notes.get(desc_start + align(desc_size)..)?;
+    }
+    None
+}
+
+/// GNU build id of the loaded shared object, read from its mapped note segments
+pub fn loaded_build_id() -> Option<String> {
+    struct Search {
+        address: usize,
+        build_id: Option<String>,
+    }
+
+    unsafe extern "C" fn visit(
+        info: *mut libc::dl_phdr_info,
+        _size: usize,
+        data: *mut c_void,
+    ) -> c_int {
+        let info = &*info;
+        let search = &mut *(data as *mut Search);
+        let headers = slice::from_raw_parts(info.dlpi_phdr, info.dlpi_phnum as usize);
+        let base = info.dlpi_addr as usize;
+
+        let contains_symbol = headers.iter().any(|header| {
+            let start = base + header.p_vaddr as usize;
+            header.p_type == libc::PT_LOAD
+                && (start..start + header.p_memsz as usize).contains(&search.address)
+        });
+        if !contains_symbol {
+            return 0;
+        }
+        search.build_id = headers
+            .iter()
+            .filter(|header| header.p_type == libc::PT_NOTE)
+            .find_map(|header| {
+                let notes = slice::from_raw_parts(
+                    (base + header.p_vaddr as usize) as *const u8,
+                    header.p_memsz as usize,
+                );
+                build_id_note(notes)
+            });
+        1
+    }
+
+    let mut search = Search {
+        address: bindgen::bbfree as usize,
+        build_id: None,
+    };
+    unsafe { libc::dl_iterate_phdr(Some(visit), &mut search as *mut Search as *mut c_void) };
+    search.build_id
+}
+
+/// Version of the loaded shared object
+///
+/// barretenberg sets no SOVERSION and exports no version, so the loaded
+/// library is identified by its GNU build id. When that matches the library
+/// `barretenberg-sys` was built against, this is the version recorded at build
+/// time; otherwise `None`.
+pub fn loaded_version() -> Option<String> {
+    let build_id = loaded_build_id()?;
+    (build_id == bindgen::LINKED_BB_BUILD_ID).then(|| bindgen::LINKED_BB_VERSION.to_string())
+}
+
+/// Check that the loaded barretenberg is the one the bindings were generated for
+///
+/// Call once at startup, before any proving or verification.
+pub fn check_loaded_version() -> Result<(), BbError> {
+    let found = loaded_build_id();
+    if bindgen::LINKED_BB_BUILD_ID.is_empty()
+        || found.as_deref() != Some(bindgen::LINKED_BB_BUILD_ID)
+    {
+        return Err(BbError::LibraryMismatch {
+            path: loaded_library_path()
+                .map_or_else(|| "unknown".to_string(), |path| path.display().to_string()),
+            expected: bindgen::LINKED_BB_BUILD_ID.to_string(),
+            found: found.unwrap_or_default(),
+        });
+    }
+    if bindgen::LINKED_BB_VERSION != bindgen::BINDINGS_BB_VERSION {
+        return Err(BbError::VersionMismatch {
+            expected: bindgen::BINDINGS_BB_VERSION.to_string(),
+            found: bindgen::LINKED_BB_VERSION.to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(name: &[u8], kind: u32, desc: &[u8]) -> Vec<u8> {
+        let pad = |bytes: &mut Vec<u8>| bytes.resize(bytes.len().next_multiple_of(4), 0);
+        let mut note = Vec::new();
+        note.extend_from_slice(&(name.len() as u32).to_ne_bytes());
+        note.extend_from_slice(&(desc.len() as u32).to_ne_bytes());
+        note.extend_from_slice(&kind.to_ne_bytes());
+        note.extend_from_slice(name);
+        pad(&mut note);
+        note.extend_from_slice(desc);
+        pad(&mut note);
+        note
+    }
+
+    #[test]
+    fn finds_gnu_build_id_note() {
+        let mut notes = note(b"GNU\0", 1, &[0; 16]);
+        notes.extend(note(b"GNU\0", 3, &[0xab, 0x01, 0xff]));
+        assert_eq!(build_id_note(&notes).as_deref(), Some("ab01ff"));
+        assert_eq!(build_id_note(&note(b"Go\0", 3, &[1])), None);
+    }
+}
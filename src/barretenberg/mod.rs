@@ -1,2 +1,5 @@
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
 pub mod srs;
 pub mod utils;
+pub mod version;
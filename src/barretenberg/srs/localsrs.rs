@@ -1,4 +1,4 @@
-use crate::barretenberg::srs::G2;
+use crate::{barretenberg::srs::G2, error::BbError};
 
 use super::Srs;
 use std::fs;
@@ -8,10 +8,17 @@ pub struct LocalSrs(pub Srs);
 const SRS_DEFAULT_PATH: &str = "srs.local";
 
 impl LocalSrs {
-    pub fn new(num_points: u32, path: Option<&str>) -> Self {
-        let file = fs::read(path.unwrap_or(SRS_DEFAULT_PATH)).unwrap();
-        let srs: Srs = bincode::deserialize(&file).unwrap();
-        LocalSrs(srs.get(num_points))
+    pub fn new(num_points: u32, path: Option<&str>) -> Result<Self, BbError> {
+        let file = fs::read(path.unwrap_or(SRS_DEFAULT_PATH)).map_err(BbError::SrsIo)?;
+        let srs: Srs =
+            bincode::deserialize(&file).map_err(|e| BbError::SrsFormat(e.to_string()))?;
+        if srs.num_points < num_points {
+            return Err(BbError::SrsFormat(format!(
+                "local SRS has {} points, {} required",
+                srs.num_points, num_points
+            )));
+        }
+        Ok(LocalSrs(srs.get(num_points)))
     }
 
     /**
@@ -22,11 +29,17 @@ impl LocalSrs {
      * @param num_points The number of points for G1
      * @param path The path to the .dat file
      */
-    pub fn from_dat_file(num_points: u32, path: Option<&str>) -> Self {
-        let file = fs::read(path.unwrap_or(SRS_DEFAULT_PATH)).unwrap();
+    pub fn from_dat_file(num_points: u32, path: Option<&str>) -> Result<Self, BbError> {
+        let file = fs::read(path.unwrap_or(SRS_DEFAULT_PATH)).map_err(BbError::SrsIo)?;
 
         const G1_START: u32 = 28;
         let g1_end: u32 = G1_START + num_points * 64 - 1;
+        if file.len() <= g1_end as usize {
+            return Err(BbError::SrsFormat(format!(
+                "SRS .dat file too short for {} points",
+                num_points
+            )));
+        }
 
         let srs: Srs = Srs {
             num_points: num_points,
@@ -34,18 +47,37 @@ impl LocalSrs {
             g2_data: G2.to_vec(),
         };
 
-        LocalSrs(srs.get(num_points))
+        Ok(LocalSrs(srs.get(num_points)))
     }
 
-    pub fn save(&self, path: Option<&str>) {
-        fs::write(
-            path.unwrap_or(SRS_DEFAULT_PATH),
-            bincode::serialize(&self.0).unwrap(),
-        )
-        .unwrap();
+    pub fn save(&self, path: Option<&str>) -> Result<(), BbError> {
+        let data = bincode::serialize(&self.0).map_err(|e| BbError::SrsFormat(e.to_string()))?;
+        fs::write(path.unwrap_or(SRS_DEFAULT_PATH), data).map_err(BbError::SrsIo)?;
+        Ok(())
     }
 
     pub fn to_srs(self) -> Srs {
         self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_missing_and_malformed_files() {
+        assert!(matches!(
+            LocalSrs::new(1, Some("does-not-exist.srs")),
+            Err(BbError::SrsIo(_))
+        ));
+
+        let path = std::env::temp_dir().join("barretenberg-malformed.srs");
+        fs::write(&path, [1, 2, 3]).unwrap();
+        assert!(matches!(
+            LocalSrs::new(1, path.to_str()),
+            Err(BbError::SrsFormat(_))
+        ));
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     barretenberg::utils::{compute_subgroup_size, get_circuit_size},
+    error::BbError,
     init_slab_allocator_safe, srs_init_safe,
 };
 
@@ -38,26 +39,21 @@ impl Srs {
     }
 }
 
-pub async fn get_srs(
-    subgroup_size: u32,
-    srs_path: Option<&str>,
-) -> Result<Srs, Box<dyn std::error::Error + Send + Sync>> {
+pub async fn get_srs(subgroup_size: u32, srs_path: Option<&str>) -> Result<Srs, BbError> {
     match srs_path {
         Some(path) => {
             if path.ends_with(".dat") {
                 // Interpret as a .dat file
-                let local_srs = localsrs::LocalSrs::from_dat_file(subgroup_size + 1, Some(path));
+                let local_srs = localsrs::LocalSrs::from_dat_file(subgroup_size + 1, Some(path))?;
                 Ok(local_srs.to_srs())
             } else {
                 // Otherwise interpret as a .local file (i.e. a serialized SRS struct)
-                let local_srs = localsrs::LocalSrs::new(subgroup_size + 1, Some(path));
+                let local_srs = localsrs::LocalSrs::new(subgroup_size + 1, Some(path))?;
                 Ok(local_srs.to_srs())
             }
         }
         None => {
-            eprintln!("IN NET SRS");
             let net_srs = netsrs::NetSrs::new(subgroup_size + 1);
-            eprintln!("{:?}", net_srs);
 
             // Pobierz SRS async i następnie wyciągnij owned wartość
             let _ = net_srs.get_srs().await?;
@@ -66,24 +62,16 @@ pub async fn get_srs(
     }
 }
 
-pub async fn setup_srs(circuit_size: u32, srs_path: Option<&str>) -> Result<u32, String> {
-    eprintln!("=== SRS Setup Debug ===");
-    eprintln!("Circuit size: {}", circuit_size);
-
+pub async fn setup_srs(circuit_size: u32, srs_path: Option<&str>) -> Result<u32, BbError> {
     // 1) Calculate subgroup size
     let subgroup_size = compute_subgroup_size(circuit_size);
-    eprintln!("Subgroup size: {}", subgroup_size);
 
     // 2) Get SRS data (await the async function)
-    eprintln!("Getting SRS data...");
-    let srs = get_srs(subgroup_size, srs_path)
-        .await
-        .map_err(|e| format!("Failed to get SRS: {}", e))?;
+    let srs = get_srs(subgroup_size, srs_path).await?;
 
     // 3) Validate data
     srs_init_safe(&srs.g1_data, srs.num_points, &srs.g2_data);
 
-    eprintln!("SRS initialized successfully!");
     Ok(srs.num_points)
 }
 
@@ -91,7 +79,7 @@ pub async fn setup_srs_from_bytecode(
     circuit_bytecode: &str,
     srs_path: Option<&str>,
     recursive: bool,
-) -> Result<u32, String> {
+) -> Result<u32, BbError> {
     let circuit_size = get_circuit_size(circuit_bytecode, recursive);
     setup_srs(circuit_size, srs_path).await
 }
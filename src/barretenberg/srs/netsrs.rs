@@ -1,9 +1,23 @@
-use reqwest::header::{HeaderMap, RANGE};
+use reqwest::header::{HeaderMap, HeaderValue, RANGE};
 use reqwest::Client;
 use std::sync::Arc;
 use tokio::sync::OnceCell;
 
-use crate::barretenberg::srs::Srs;
+use crate::{barretenberg::srs::Srs, error::BbError};
+
+fn network_error(e: impl std::fmt::Display) -> BbError {
+    BbError::SrsNetwork(e.to_string())
+}
+
+fn range_header(num_points: u32) -> Result<HeaderMap, BbError> {
+    let g1_end = num_points * 64 - 1;
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        RANGE,
+        HeaderValue::from_str(&format!("bytes=0-{}", g1_end)).map_err(network_error)?,
+    );
+    Ok(headers)
+}
 
 #[derive(Debug, Clone)]
 pub struct NetSrs {
@@ -30,13 +44,13 @@ impl NetSrs {
         }
     }
 
-    pub async fn get_srs(&self) -> Result<&Srs, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn get_srs(&self) -> Result<&Srs, BbError> {
         self.srs
             .get_or_try_init(|| async { self.download_srs().await })
             .await
     }
 
-    async fn download_srs(&self) -> Result<Srs, Box<dyn std::error::Error + Send + Sync>> {
+    async fn download_srs(&self) -> Result<Srs, BbError> {
         // Pobierz zarówno G1 jak i G2
         let (g1_data, g2_data) =
             tokio::try_join!(self.download_g1_data(), self.download_g2_data())?;
@@ -48,93 +62,94 @@ impl NetSrs {
         })
     }
 
-    async fn download_g1_data(&self) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    async fn download_g1_data(&self) -> Result<Vec<u8>, BbError> {
         if self.num_points == 0 {
             return Ok(Vec::new());
         }
 
-        let g1_end = self.num_points * 64 - 1;
-
-        let mut headers = HeaderMap::new();
-        headers.insert(RANGE, format!("bytes=0-{}", g1_end).parse()?);
+        let headers = range_header(self.num_points)?;
 
         let client = Client::new();
         let response = client
             .get("https://crs.aztec.network/g1.dat")
             .headers(headers)
             .send()
-            .await?;
+            .await
+            .map_err(network_error)?;
 
-        let bytes = response.bytes().await?;
+        let bytes = response.bytes().await.map_err(network_error)?;
         Ok(bytes.to_vec())
     }
 
-    async fn download_g2_data(&self) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    async fn download_g2_data(&self) -> Result<Vec<u8>, BbError> {
         let client = Client::new();
         let response = client
             .get("https://crs.aztec.network/g2.dat")
             .send()
-            .await?;
+            .await
+            .map_err(network_error)?;
 
-        let bytes = response.bytes().await?;
+        let bytes = response.bytes().await.map_err(network_error)?;
         Ok(bytes.to_vec())
     }
 
     // Metody pomocnicze do pobrania strumieni (jak w TS)
-    pub async fn stream_g1_data(
-        &self,
-    ) -> Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn stream_g1_data(&self) -> Result<reqwest::Response, BbError> {
         if self.num_points == 0 {
-            return Err("Cannot stream G1 data with 0 points".into());
+            return Err(BbError::SrsState(
+                "cannot stream G1 data with 0 points".to_string(),
+            ));
         }
 
-        let g1_end = self.num_points * 64 - 1;
-        let mut headers = HeaderMap::new();
-        headers.insert(RANGE, format!("bytes=0-{}", g1_end).parse()?);
+        let headers = range_header(self.num_points)?;
 
         let client = Client::new();
         let response = client
             .get("https://crs.aztec.network/g1.dat")
             .headers(headers)
             .send()
-            .await?;
+            .await
+            .map_err(network_error)?;
 
         Ok(response)
     }
 
-    pub async fn stream_g2_data(
-        &self,
-    ) -> Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn stream_g2_data(&self) -> Result<reqwest::Response, BbError> {
         let client = Client::new();
         let response = client
             .get("https://crs.aztec.network/g2.dat")
             .send()
-            .await?;
+            .await
+            .map_err(network_error)?;
 
         Ok(response)
     }
 
     // Metoda która próbuje sklonować SRS jeśli jest dostępny
-    pub fn to_srs(self) -> Result<Srs, Box<dyn std::error::Error + Send + Sync>> {
+    pub fn to_srs(self) -> Result<Srs, BbError> {
         self.srs
             .get()
             .cloned()
-            .ok_or("SRS not initialized. Call get_srs() first.".into())
+            .ok_or_else(|| BbError::SrsState("not initialized, call get_srs() first".to_string()))
     }
 
     // Alternatywna metoda która move'uje wartość bez klonowania
-    pub fn try_to_srs(self) -> Result<Srs, Box<dyn std::error::Error + Send + Sync>> {
+    pub fn try_to_srs(self) -> Result<Srs, BbError> {
         match Arc::try_unwrap(self.srs) {
             Ok(once_cell) => match once_cell.into_inner() {
                 Some(srs) => Ok(srs),
-                None => Err("SRS not initialized. Call get_srs() first.".into()),
+                None => Err(BbError::SrsState(
+                    "not initialized, call get_srs() first".to_string(),
+                )),
             },
-            Err(_) => Err("Cannot extract SRS: multiple references exist".into()),
+            Err(_) => Err(BbError::SrsState(
+                "cannot extract, multiple references exist".to_string(),
+            )),
         }
     }
 
     // Metoda która zwraca referencję do SRS
-    pub async fn as_srs(&self) -> Result<&Srs, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn as_srs(&self) -> Result<&Srs, BbError> {
         self.get_srs().await
     }
 }
@@ -154,50 +169,47 @@ impl NetGrumpkinSrs {
         }
     }
 
-    pub async fn get_g1_data(&self) -> Result<&Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn get_g1_data(&self) -> Result<&Vec<u8>, BbError> {
         self.g1_data
             .get_or_try_init(|| async { self.download_g1_data().await })
             .await
     }
 
-    async fn download_g1_data(&self) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    async fn download_g1_data(&self) -> Result<Vec<u8>, BbError> {
         if self.num_points == 0 {
             return Ok(Vec::new());
         }
 
-        let g1_end = self.num_points * 64 - 1;
-
-        let mut headers = HeaderMap::new();
-        headers.insert(RANGE, format!("bytes=0-{}", g1_end).parse()?);
+        let headers = range_header(self.num_points)?;
 
         let client = Client::new();
         let response = client
             .get("https://crs.aztec.network/grumpkin_g1.dat")
             .headers(headers)
             .send()
-            .await?;
+            .await
+            .map_err(network_error)?;
 
-        let bytes = response.bytes().await?;
+        let bytes = response.bytes().await.map_err(network_error)?;
         Ok(bytes.to_vec())
     }
 
-    pub async fn stream_g1_data(
-        &self,
-    ) -> Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn stream_g1_data(&self) -> Result<reqwest::Response, BbError> {
         if self.num_points == 0 {
-            return Err("Cannot stream G1 data with 0 points".into());
+            return Err(BbError::SrsState(
+                "cannot stream G1 data with 0 points".to_string(),
+            ));
         }
 
-        let g1_end = self.num_points * 64 - 1;
-        let mut headers = HeaderMap::new();
-        headers.insert(RANGE, format!("bytes=0-{}", g1_end).parse()?);
+        let headers = range_header(self.num_points)?;
 
         let client = Client::new();
         let response = client
             .get("https://crs.aztec.network/grumpkin_g1.dat")
             .headers(headers)
             .send()
-            .await?;
+            .await
+            .map_err(network_error)?;
 
         Ok(response)
     }
@@ -1,25 +1,36 @@
+#[cfg(bb_starknet)]
+use crate::acir_write_vk_ultra_starknet_honk_safe;
 use crate::{
-    bindgen::acir_get_circuit_sizes, circuits::decode_circuit, encode_raw_buffer,
-    get_circuit_sizes_safe,
+    acir_write_vk_ultra_honk_safe, acir_write_vk_ultra_keccak_honk_safe, circuits::decode_circuit,
+    error::BbError, flavor::OracleHashFlavor, get_circuit_sizes_safe,
 };
 
+/// Compute the UltraHonk verification key of a circuit
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode to compute the verification key for
+/// * flavor: The transcript hash the proofs will be created with
+///
+/// # Returns
+///
+/// The serialized verification key
 pub fn get_honk_verification_key(
     circuit_bytecode: &str,
-    recursive: bool,
-) -> Result<Vec<u8>, String> {
-    let (_, acir_buffer_uncompressed) =
-        decode_circuit(circuit_bytecode).map_err(|e| format!("Failed to decode circuit: {}", e))?;
-
-    // acir_get_verification_key expects (in_ptr, out: *mut *mut u8)
-    // This seems to need an ACIR composer pointer, not just the buffer
-    // For now, let's comment this out as it needs a different approach
-
-    // You would need to:
-    // 1. Create an ACIR composer with acir_new_acir_composer
-    // 2. Initialize it with the circuit
-    // 3. Then get the verification key
-
-    Err("get_honk_verification_key needs to be implemented with proper ACIR composer".to_string())
+    flavor: OracleHashFlavor,
+) -> Result<Vec<u8>, BbError> {
+    flavor.check_available()?;
+    let (_, acir_buffer_uncompressed) = decode_circuit(circuit_bytecode)?;
+    match flavor {
+        OracleHashFlavor::Poseidon2 => acir_write_vk_ultra_honk_safe(&acir_buffer_uncompressed),
+        OracleHashFlavor::Keccak => acir_write_vk_ultra_keccak_honk_safe(&acir_buffer_uncompressed),
+        #[cfg(bb_starknet)]
+        OracleHashFlavor::Starknet => {
+            acir_write_vk_ultra_starknet_honk_safe(&acir_buffer_uncompressed)
+        }
+        #[cfg(not(bb_starknet))]
+        OracleHashFlavor::Starknet => unreachable!("rejected by check_available"),
+    }
 }
 
 pub fn compute_subgroup_size(circuit_size: u32) -> u32 {
@@ -0,0 +1,192 @@
+//! Version of the linked barretenberg and the envelope recording it in serialized artifacts
+//!
+//! Envelope layout, all integers big-endian:
+//!
+//! | magic (4) | format (1) | version len (2) | version | git hash len (2) | git hash | payload |
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{bindgen, error::BbError};
+
+const ENVELOPE_FORMAT: u8 = 1;
+
+/// Version and source commit of a barretenberg build
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BbVersion {
+    pub version: String,
+    pub git_hash: String,
+}
+
+impl BbVersion {
+    fn major_minor(&self) -> Option<(u64, u64)> {
+        let mut parts = self.version.trim_start_matches('v').split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        Some((major, minor))
+    }
+
+    /// Whether artifacts produced by `other` can be verified by this version
+    ///
+    /// Releases are compatible within the same major and minor version;
+    /// unparseable versions only match themselves.
+    pub fn is_compatible_with(&self, other: &BbVersion) -> bool {
+        match (self.major_minor(), other.major_minor()) {
+            (Some(ours), Some(theirs)) => ours == theirs,
+            _ => self.version == other.version,
+        }
+    }
+}
+
+impl fmt::Display for BbVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.version, self.git_hash)
+    }
+}
+
+/// Version of the linked barretenberg
+///
+/// With the `dynamic` feature this is `"unknown"` unless the loaded shared
+/// object is the one `barretenberg-sys` was built against.
+pub fn version() -> BbVersion {
+    #[cfg(feature = "dynamic")]
+    if super::dynamic::loaded_version().is_none() {
+        return BbVersion {
+            version: "unknown".to_string(),
+            git_hash: "unknown".to_string(),
+        };
+    }
+
+    BbVersion {
+        version: bindgen::LINKED_BB_VERSION.to_string(),
+        git_hash: bindgen::LINKED_BB_GIT_HASH.to_string(),
+    }
+}
+
+/// Refuse artifacts produced by a barretenberg incompatible with the linked one
+pub(crate) fn check_compatible(produced_by: &BbVersion) -> Result<(), BbError> {
+    let linked = version();
+    if linked.is_compatible_with(produced_by) {
+        Ok(())
+    } else {
+        Err(BbError::VersionMismatch {
+            expected: linked.to_string(),
+            found: produced_by.to_string(),
+        })
+    }
+}
+
+fn push_str(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn envelope_error(magic: &[u8; 4], reason: &str) -> BbError {
+    BbError::Envelope(format!("{}: {}", String::from_utf8_lossy(magic), reason))
+}
+
+/// Prefix `payload` with `magic` and the version it was produced by
+pub(crate) fn wrap(magic: &[u8; 4], produced_by: &BbVersion, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 64);
+    out.extend_from_slice(magic);
+    out.push(ENVELOPE_FORMAT);
+    push_str(&mut out, &produced_by.version);
+    push_str(&mut out, &produced_by.git_hash);
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Split an envelope written by `wrap` into the producing version and the payload
+pub(crate) fn unwrap<'a>(
+    magic: &[u8; 4],
+    bytes: &'a [u8],
+) -> Result<(BbVersion, &'a [u8]), BbError> {
+    let mut reader = Reader::new(bytes, magic);
+    if reader.take(4)? != magic {
+        return Err(envelope_error(magic, "wrong magic"));
+    }
+    let format = reader.take(1)?[0];
+    if format != ENVELOPE_FORMAT {
+        return Err(envelope_error(
+            magic,
+            &format!("unsupported format {}", format),
+        ));
+    }
+    let version = reader.string()?;
+    let git_hash = reader.string()?;
+    Ok((BbVersion { version, git_hash }, reader.rest()))
+}
+
+/// Bounds-checked cursor over an envelope
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    magic: &'a [u8; 4],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8], magic: &'a [u8; 4]) -> Self {
+        Reader { bytes, magic }
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], BbError> {
+        if self.bytes.len() < len {
+            return Err(envelope_error(self.magic, "truncated"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, BbError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn string(&mut self) -> Result<String, BbError> {
+        let len = self.take(2)?;
+        let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+        String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|_| envelope_error(self.magic, "version is not UTF-8"))
+    }
+
+    /// A `u32` length followed by that many bytes
+    pub(crate) fn bytes(&mut self) -> Result<&'a [u8], BbError> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    pub(crate) fn rest(self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bb(version: &str) -> BbVersion {
+        BbVersion {
+            version: version.to_string(),
+            git_hash: "abc".to_string(),
+        }
+    }
+
+    #[test]
+    fn compatible_within_minor() {
+        assert!(bb("0.87.0").is_compatible_with(&bb("v0.87.3")));
+        assert!(!bb("0.87.0").is_compatible_with(&bb("0.86.0")));
+        assert!(!bb("unknown").is_compatible_with(&bb("0.87.0")));
+    }
+
+    #[test]
+    fn envelope_round_trip() {
+        let wrapped = wrap(b"TEST", &bb("0.87.0"), b"payload");
+        let (version, payload) = unwrap(b"TEST", &wrapped).unwrap();
+        assert_eq!(version, bb("0.87.0"));
+        assert_eq!(payload, b"payload");
+
+        assert!(unwrap(b"OTHR", &wrapped).is_err());
+        assert!(unwrap(b"TEST", &wrapped[..6]).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+use std::ffi::c_void;
+use std::ptr::{self, NonNull};
+
+use crate::{bindgen, error::BbError};
+
+const LEN_PREFIX: usize = 4;
+
+/// Owned buffer allocated by barretenberg and handed back through an
+/// out-pointer (`*mut *mut u8`) or `vec_out_buf` argument.
+///
+/// barretenberg serializes its outputs as a 4-byte big-endian length prefix
+/// followed by the payload. The buffer is released with `bbfree` on drop.
+#[derive(Debug)]
+pub struct BbBuffer {
+    ptr: NonNull<u8>,
+}
+
+// The buffer is exclusively owned heap memory, not tied to the thread that
+// allocated it.
+unsafe impl Send for BbBuffer {}
+
+impl BbBuffer {
+    /// Take ownership of a pointer written by barretenberg
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null or point to a length-prefixed buffer allocated by
+    /// barretenberg that is not owned by anything else.
+    pub unsafe fn from_raw(ptr: *mut u8) -> Result<Self, BbError> {
+        NonNull::new(ptr)
+            .map(|ptr| BbBuffer { ptr })
+            .ok_or_else(|| BbError::FfiOutput("barretenberg returned a null buffer".to_string()))
+    }
+
+    /// Call `f` with an out pointer and take ownership of what it writes
+    ///
+    /// # Safety
+    ///
+    /// `f` must store either null or a barretenberg-allocated buffer into the
+    /// pointer it is given.
+    pub unsafe fn from_out_ptr(f: impl FnOnce(*mut *mut u8)) -> Result<Self, BbError> {
+        let mut raw: *mut u8 = ptr::null_mut();
+        f(&mut raw);
+        Self::from_raw(raw)
+    }
+
+    /// The 4-byte big-endian prefix: a byte count for byte vectors, an element
+    /// count for field vectors
+    pub fn prefix(&self) -> u32 {
+        let mut len = [0u8; LEN_PREFIX];
+        unsafe { ptr::copy_nonoverlapping(self.ptr.as_ptr(), len.as_mut_ptr(), LEN_PREFIX) };
+        u32::from_be_bytes(len)
+    }
+
+    /// The whole buffer, prefix included, for elements of `element_size` bytes
+    pub fn as_slice_with_prefix(&self, element_size: usize) -> &[u8] {
+        let len = LEN_PREFIX + self.prefix() as usize * element_size;
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), len) }
+    }
+
+    /// The payload of a length-prefixed byte vector
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.as_slice_with_prefix(1)[LEN_PREFIX..]
+    }
+
+    /// The payload of a length-prefixed vector of 32-byte field elements
+    pub fn as_fields(&self) -> &[[u8; 32]] {
+        let payload = &self.as_slice_with_prefix(32)[LEN_PREFIX..];
+        unsafe {
+            std::slice::from_raw_parts(payload.as_ptr() as *const [u8; 32], payload.len() / 32)
+        }
+    }
+}
+
+impl Drop for BbBuffer {
+    fn drop(&mut self) {
+        unsafe { bindgen::bbfree(self.ptr.as_ptr() as *mut c_void) };
+    }
+}
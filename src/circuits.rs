@@ -4,6 +4,8 @@ use acir::{circuit::Program, FieldElement};
 use base64::engine::{general_purpose, Engine};
 use flate2::bufread::GzDecoder;
 
+use crate::error::BbError;
+
 /// Get the acir buffer (compressed) from the circuit bytecode
 ///
 /// # Arguments
@@ -13,10 +15,8 @@ use flate2::bufread::GzDecoder;
 /// # Returns
 ///
 /// The acir buffer (compressed)
-pub fn get_acir_buffer(circuit_bytecode: &str) -> Result<Vec<u8>, String> {
-    let acir_buffer = general_purpose::STANDARD
-        .decode(circuit_bytecode)
-        .map_err(|e| e.to_string())?;
+pub fn get_acir_buffer(circuit_bytecode: &str) -> Result<Vec<u8>, BbError> {
+    let acir_buffer = general_purpose::STANDARD.decode(circuit_bytecode)?;
 
     Ok(acir_buffer)
 }
@@ -30,12 +30,12 @@ pub fn get_acir_buffer(circuit_bytecode: &str) -> Result<Vec<u8>, String> {
 /// # Returns
 ///
 /// The uncompressed acir buffer
-pub fn uncompress_acir_buffer(acir_buffer: Vec<u8>) -> Result<Vec<u8>, String> {
+pub fn uncompress_acir_buffer(acir_buffer: Vec<u8>) -> Result<Vec<u8>, BbError> {
     let mut decoder = GzDecoder::new(acir_buffer.as_slice());
     let mut acir_buffer_uncompressed = Vec::<u8>::new();
     decoder
         .read_to_end(&mut acir_buffer_uncompressed)
-        .map_err(|e| e.to_string())?;
+        .map_err(BbError::Gzip)?;
 
     Ok(acir_buffer_uncompressed)
 }
@@ -49,7 +49,7 @@ pub fn uncompress_acir_buffer(acir_buffer: Vec<u8>) -> Result<Vec<u8>, String> {
 /// # Returns
 ///
 /// The acir buffer (uncompressed)
-pub fn get_acir_buffer_uncompressed(circuit_bytecode: &str) -> Result<Vec<u8>, String> {
+pub fn get_acir_buffer_uncompressed(circuit_bytecode: &str) -> Result<Vec<u8>, BbError> {
     let acir_buffer = get_acir_buffer(circuit_bytecode)?;
     uncompress_acir_buffer(acir_buffer)
 }
@@ -63,7 +63,7 @@ pub fn get_acir_buffer_uncompressed(circuit_bytecode: &str) -> Result<Vec<u8>, S
 /// # Returns
 ///
 /// The acir buffer and the uncompressed acir buffer
-pub fn decode_circuit(circuit_bytecode: &str) -> Result<(Vec<u8>, Vec<u8>), String> {
+pub fn decode_circuit(circuit_bytecode: &str) -> Result<(Vec<u8>, Vec<u8>), BbError> {
     let acir_buffer = get_acir_buffer(circuit_bytecode)?;
     let acir_buffer_uncompressed = get_acir_buffer_uncompressed(circuit_bytecode)?;
 
@@ -79,7 +79,8 @@ pub fn decode_circuit(circuit_bytecode: &str) -> Result<(Vec<u8>, Vec<u8>), Stri
 /// # Returns
 ///
 /// The program
-pub fn get_program(circuit_bytecode: &str) -> Result<Program<FieldElement>, String> {
+pub fn get_program(circuit_bytecode: &str) -> Result<Program<FieldElement>, BbError> {
     let acir_buffer: Vec<u8> = get_acir_buffer(circuit_bytecode)?;
-    Program::deserialize_program(&acir_buffer).map_err(|e| e.to_string())
+    Program::deserialize_program(&acir_buffer)
+        .map_err(|e| BbError::AcirDeserialization(e.to_string()))
 }
@@ -0,0 +1,117 @@
+use thiserror::Error;
+
+use crate::{execute::ExecutionError, flavor::OracleHashFlavor};
+
+/// Crate-wide error type returned by every fallible wrapper
+#[derive(Debug, Error)]
+pub enum BbError {
+    /// The circuit bytecode is not valid base64
+    #[error("failed to decode base64 bytecode")]
+    Base64Decode(#[from] base64::DecodeError),
+
+    /// The decoded bytecode is not a valid gzip stream
+    #[error("failed to decompress gzip bytecode")]
+    Gzip(#[source] std::io::Error),
+
+    /// The decompressed bytecode is not a valid ACIR program
+    #[error("failed to deserialize ACIR program: {0}")]
+    AcirDeserialization(String),
+
+    /// A nargo artifact could not be read or parsed
+    #[error("invalid Noir artifact: {0}")]
+    Artifact(String),
+
+    /// A named circuit input does not match its ABI type
+    #[error("invalid input `{path}`{}: {message}", line_suffix(.line))]
+    InvalidInput {
+        path: String,
+        /// Line of the key in the inputs file, when read from one
+        line: Option<usize>,
+        message: String,
+    },
+
+    /// An inputs file is not valid TOML or JSON
+    #[error("failed to parse inputs: {0}")]
+    InputSyntax(String),
+
+    /// The ACVM failed to solve the circuit
+    #[error("circuit execution failed: {0}")]
+    Execution(Box<ExecutionError>),
+
+    /// A buffer returned by barretenberg is null or shorter than advertised
+    #[error("malformed barretenberg output: {0}")]
+    FfiOutput(String),
+
+    /// The caller's public input count disagrees with the circuit
+    #[error("circuit has {actual} public inputs, caller expected {expected}")]
+    PublicInputsMismatch { expected: usize, actual: usize },
+
+    /// A value is not a canonical BN254 field element
+    #[error("invalid field element: {0}")]
+    InvalidField(String),
+
+    /// A Schnorr key, signature or multisig round is invalid
+    #[error("schnorr: {0}")]
+    Schnorr(String),
+
+    /// AES input has an invalid length or padding
+    #[error("aes128: {0}")]
+    Aes(String),
+
+    /// The linked barretenberg differs from the one the bindings were generated for
+    #[error("barretenberg version mismatch: expected {expected}, found {found}")]
+    VersionMismatch { expected: String, found: String },
+
+    /// The loaded shared barretenberg is not the library the crate was built against
+    #[error("loaded barretenberg {path} has build id {found:?}, expected {expected:?}")]
+    LibraryMismatch {
+        path: String,
+        expected: String,
+        found: String,
+    },
+
+    /// A serialized proof or verification key envelope is malformed
+    #[error("invalid envelope: {0}")]
+    Envelope(String),
+
+    /// barretenberg rejected the proof
+    #[error("proof verification failed")]
+    VerificationFailed,
+
+    /// The linked barretenberg was built without this flavor's entry points
+    #[error("{0:?} flavor unavailable: barretenberg was built without STARKNET_GARAGA_FLAVORS")]
+    FlavorUnavailable(OracleHashFlavor),
+
+    /// Reading or writing a local SRS file failed
+    #[error("failed to read SRS")]
+    SrsIo(#[source] std::io::Error),
+
+    /// A local SRS file is malformed or holds too few points
+    #[error("invalid SRS: {0}")]
+    SrsFormat(String),
+
+    /// A downloaded SRS was used before it was fetched, or is still shared
+    #[error("SRS not available: {0}")]
+    SrsState(String),
+
+    /// Downloading the SRS from the CRS server failed
+    #[error("failed to download SRS: {0}")]
+    SrsNetwork(String),
+
+    /// A witness string is not a valid field element
+    #[error("invalid witness {index} ({value:?}): {reason}")]
+    InvalidWitness {
+        index: usize,
+        value: String,
+        reason: String,
+    },
+
+    /// A witness map or stack could not be converted or (de)serialized
+    #[error("failed to (de)serialize witness: {0}")]
+    WitnessSerialization(String),
+}
+
+fn line_suffix(line: &Option<usize>) -> String {
+    line.map(|line| format!(" (line {})", line))
+        .unwrap_or_default()
+}
@@ -1,12 +1,125 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
 use acir::{
+    circuit::{Program, ResolvedOpcodeLocation},
     native_types::{WitnessMap, WitnessStack},
     FieldElement,
 };
+use acvm::pwg::OpcodeResolutionError;
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
-use nargo::foreign_calls::default::DefaultForeignCallBuilder;
+use nargo::errors::{ExecutionError as NargoExecutionError, NargoError};
+use nargo::foreign_calls::{
+    default::DefaultForeignCallBuilder, print::PrintOutput, ForeignCallExecutor,
+};
 use nargo::ops::execute_program;
 
-use crate::circuits::get_program;
+use crate::{artifact::NoirArtifact, circuits::get_program, error::BbError};
+
+/// A position in a Noir source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub path: PathBuf,
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+}
+
+impl SourceLocation {
+    /// Location of the byte `offset` into `source`
+    pub(crate) fn new(path: PathBuf, source: &str, offset: usize) -> Self {
+        let mut offset = offset.min(source.len());
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        SourceLocation {
+            path,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path.display(), self.line, self.column)
+    }
+}
+
+/// Why the ACVM failed to solve a circuit
+#[derive(Debug, Clone)]
+pub struct ExecutionError {
+    /// nargo's description of the failure
+    pub message: String,
+    /// Message of the failing `assert`, decoded with the ABI error types when
+    /// the circuit was executed from an artifact
+    pub assertion_message: Option<String>,
+    /// Failing opcode and the ACIR calls leading to it, outermost first
+    pub call_stack: Vec<ResolvedOpcodeLocation>,
+    /// Noir source locations of `call_stack`, outermost first, when the
+    /// artifact has debug symbols
+    pub source_locations: Vec<SourceLocation>,
+}
+
+impl ExecutionError {
+    pub(crate) fn new(error: &NargoError<FieldElement>, artifact: Option<&NoirArtifact>) -> Self {
+        let call_stack = match error {
+            NargoError::ExecutionError(NargoExecutionError::AssertionFailed(_, call_stack, _))
+            | NargoError::ExecutionError(NargoExecutionError::SolvingError(_, Some(call_stack))) => {
+                call_stack.clone()
+            }
+            _ => Vec::new(),
+        };
+        let brillig_function_id = match error {
+            NargoError::ExecutionError(NargoExecutionError::AssertionFailed(_, _, function_id)) => {
+                *function_id
+            }
+            NargoError::ExecutionError(NargoExecutionError::SolvingError(
+                OpcodeResolutionError::BrilligFunctionFailed { function_id, .. },
+                _,
+            )) => Some(*function_id),
+            _ => None,
+        };
+        let no_error_types = BTreeMap::new();
+        let error_types = artifact.map_or(&no_error_types, |artifact| &artifact.abi.error_types);
+        let source_locations = artifact
+            .map(|artifact| {
+                call_stack
+                    .iter()
+                    .flat_map(|location| artifact.source_locations(location, brillig_function_id))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        ExecutionError {
+            message: error.to_string(),
+            assertion_message: error.user_defined_failure_message(error_types),
+            call_stack,
+            source_locations,
+        }
+    }
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(message) = &self.assertion_message {
+            if !self.message.contains(message.as_str()) {
+                write!(f, ": {}", message)?;
+            }
+        }
+        if let Some(location) = self.source_locations.last() {
+            write!(f, " at {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ExecutionError {}
 
 /// Execute the circuit and return the serialized solved witness stack
 ///
@@ -14,6 +127,8 @@ use crate::circuits::get_program;
 ///
 /// * circuit_bytecode: The circuit bytecode to execute
 /// * initial_witness: The initial witness to use for the execution
+/// * print_output: Where `print`/`println` output goes, e.g. `PrintOutput::None`
+///   to drop it or `PrintOutput::String(&mut captured)` to collect it
 ///
 /// # Returns
 ///
@@ -21,19 +136,218 @@ use crate::circuits::get_program;
 pub fn execute(
     circuit_bytecode: &str,
     initial_witness: WitnessMap<FieldElement>,
-) -> Result<WitnessStack<FieldElement>, String> {
+    print_output: PrintOutput<'_>,
+) -> Result<WitnessStack<FieldElement>, BbError> {
+    let mut foreign_call_executor = DefaultForeignCallBuilder::default()
+        .with_output(print_output)
+        .build();
+    execute_with_oracles(
+        circuit_bytecode,
+        initial_witness,
+        &mut foreign_call_executor,
+    )
+}
+
+/// Solved witness stack of an execution and the lines the circuit printed
+#[derive(Debug, Clone)]
+pub struct ExecutionOutput {
+    pub witness_stack: WitnessStack<FieldElement>,
+    /// Output of `print`/`println`, split into lines
+    pub printed: Vec<String>,
+}
+
+/// Execute the circuit, capturing `print`/`println` output instead of writing it to stdout
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode to execute
+/// * initial_witness: The initial witness to use for the execution
+///
+/// # Returns
+///
+/// The Witness Stack and the printed lines
+pub fn execute_capturing_output(
+    circuit_bytecode: &str,
+    initial_witness: WitnessMap<FieldElement>,
+) -> Result<ExecutionOutput, BbError> {
+    let mut printed = String::new();
+    let witness_stack = execute(
+        circuit_bytecode,
+        initial_witness,
+        PrintOutput::String(&mut printed),
+    )?;
+
+    Ok(ExecutionOutput {
+        witness_stack,
+        printed: printed.lines().map(str::to_string).collect(),
+    })
+}
+
+/// Execute the circuit, answering foreign calls with a custom executor
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode to execute
+/// * initial_witness: The initial witness to use for the execution
+/// * foreign_call_executor: Answers the circuit's oracle calls, e.g.
+///   `oracles::OracleRegistry::with_defaults()`
+///
+/// # Returns
+///
+/// The Witness Stack
+pub fn execute_with_oracles<E: ForeignCallExecutor<FieldElement>>(
+    circuit_bytecode: &str,
+    initial_witness: WitnessMap<FieldElement>,
+    foreign_call_executor: &mut E,
+) -> Result<WitnessStack<FieldElement>, BbError> {
     let program = get_program(circuit_bytecode)?;
+    execute_program_with(&program, initial_witness, foreign_call_executor, None)
+}
 
+/// Execute a decoded program, explaining failures with the artifact's ABI and
+/// debug symbols when given
+pub(crate) fn execute_program_with<E: ForeignCallExecutor<FieldElement>>(
+    program: &Program<FieldElement>,
+    initial_witness: WitnessMap<FieldElement>,
+    foreign_call_executor: &mut E,
+    artifact: Option<&NoirArtifact>,
+) -> Result<WitnessStack<FieldElement>, BbError> {
     let blackbox_solver = Bn254BlackBoxSolver::default();
-    let mut foreign_call_executor = DefaultForeignCallBuilder::default().build();
 
     let solved_witness = execute_program(
-        &program,
+        program,
         initial_witness,
         &blackbox_solver,
-        &mut foreign_call_executor,
+        foreign_call_executor,
     )
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| BbError::Execution(Box::new(ExecutionError::new(&e, artifact))))?;
 
     Ok(solved_witness)
 }
+
+#[cfg(test)]
+mod tests {
+    use acir::{
+        brillig::{
+            BitSize, HeapArray, HeapValueType, HeapVector, IntegerBitSize, MemoryAddress,
+            Opcode as BrilligOpcode, ValueOrArray,
+        },
+        circuit::{
+            brillig::{BrilligBytecode, BrilligFunctionId},
+            Circuit, Opcode,
+        },
+    };
+    use base64::engine::{general_purpose, Engine};
+
+    use super::*;
+
+    /// Bytecode of an unconstrained function that `println`s each of `lines`,
+    /// laid out like nargo's `print` oracle call: newline flag, the value, its
+    /// `PrintableType` as JSON, and whether it is a format string
+    fn println_bytecode(lines: &[&str]) -> String {
+        let u32_const = |address: u32, value: usize| BrilligOpcode::Const {
+            destination: MemoryAddress::direct(address as _),
+            bit_size: BitSize::Integer(IntegerBitSize::U32),
+            value: FieldElement::from(value as u128),
+        };
+        let field_const = |address: usize, value: u128| BrilligOpcode::Const {
+            destination: MemoryAddress::direct(address as _),
+            bit_size: BitSize::Field,
+            value: FieldElement::from(value),
+        };
+        let string_array = |pointer: u32, size: usize| {
+            (
+                ValueOrArray::HeapArray(HeapArray {
+                    pointer: MemoryAddress::direct(pointer as _),
+                    size,
+                }),
+                HeapValueType::Array {
+                    value_types: vec![HeapValueType::field()],
+                    size,
+                },
+            )
+        };
+
+        // 1: zero, 2: newline flag, 3: not a format string, 4/5: array pointers
+        let mut bytecode = vec![u32_const(1, 0), field_const(2, 1), field_const(3, 0)];
+        let mut next_free = 16;
+        for line in lines {
+            let printable_type = format!(r#"{{"kind":"string","length":{}}}"#, line.len());
+            for (pointer, text) in [(4, *line), (5, printable_type.as_str())] {
+                bytecode.push(u32_const(pointer, next_free));
+                for byte in text.bytes() {
+                    bytecode.push(field_const(next_free, byte.into()));
+                    next_free += 1;
+                }
+            }
+            let (message, message_type) = string_array(4, line.len());
+            let (printable_type, printable_type_type) = string_array(5, printable_type.len());
+            bytecode.push(BrilligOpcode::ForeignCall {
+                function: "print".to_string(),
+                destinations: Vec::new(),
+                destination_value_types: Vec::new(),
+                inputs: vec![
+                    ValueOrArray::MemoryAddress(MemoryAddress::direct(2)),
+                    message,
+                    printable_type,
+                    ValueOrArray::MemoryAddress(MemoryAddress::direct(3)),
+                ],
+                input_value_types: vec![
+                    HeapValueType::field(),
+                    message_type,
+                    printable_type_type,
+                    HeapValueType::field(),
+                ],
+            });
+        }
+        bytecode.push(BrilligOpcode::Stop {
+            return_data: HeapVector {
+                pointer: MemoryAddress::direct(1),
+                size: MemoryAddress::direct(1),
+            },
+        });
+
+        let program = Program {
+            functions: vec![Circuit {
+                opcodes: vec![Opcode::BrilligCall {
+                    id: BrilligFunctionId(0),
+                    inputs: Vec::new(),
+                    outputs: Vec::new(),
+                    predicate: None,
+                }],
+                ..Circuit::default()
+            }],
+            unconstrained_functions: vec![BrilligBytecode {
+                bytecode,
+                ..BrilligBytecode::default()
+            }],
+        };
+        general_purpose::STANDARD.encode(Program::serialize_program(&program))
+    }
+
+    #[test]
+    fn captures_printed_lines() {
+        let bytecode = println_bytecode(&["hello", "x = 42"]);
+
+        let output = execute_capturing_output(&bytecode, WitnessMap::new()).unwrap();
+        assert_eq!(output.printed, vec!["hello", "x = 42"]);
+
+        let mut printed = String::new();
+        execute(
+            &bytecode,
+            WitnessMap::new(),
+            PrintOutput::String(&mut printed),
+        )
+        .unwrap();
+        assert_eq!(printed, "hello\nx = 42\n");
+    }
+
+    #[test]
+    fn source_location_from_offset() {
+        let source = "fn main(x: Field) {\n    assert(x != 0, \"zero\");\n}\n";
+        let offset = source.find("assert").unwrap();
+        let location = SourceLocation::new(PathBuf::from("src/main.nr"), source, offset);
+        assert_eq!((location.line, location.column), (2, 5));
+        assert_eq!(location.to_string(), "src/main.nr:2:5");
+    }
+}
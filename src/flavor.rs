@@ -0,0 +1,44 @@
+use garaga_rs::calldata::full_proof_with_hints::honk::HonkFlavor;
+
+use crate::error::BbError;
+
+/// Transcript (oracle) hash used by UltraHonk when proving, computing the
+/// verification key and verifying. All three must use the same flavor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OracleHashFlavor {
+    /// barretenberg's default transcript, cheapest to verify recursively in Noir
+    #[default]
+    Poseidon2,
+    /// Keccak transcript, used by EVM and garaga Keccak verifiers
+    Keccak,
+    /// Poseidon transcript over the Starknet field, used by garaga Starknet verifiers
+    Starknet,
+}
+
+impl OracleHashFlavor {
+    /// Whether the linked barretenberg has this flavor's entry points; Starknet
+    /// needs barretenberg built with `STARKNET_GARAGA_FLAVORS`
+    pub fn is_available(self) -> bool {
+        match self {
+            OracleHashFlavor::Starknet => cfg!(bb_starknet),
+            OracleHashFlavor::Poseidon2 | OracleHashFlavor::Keccak => true,
+        }
+    }
+
+    pub(crate) fn check_available(self) -> Result<(), BbError> {
+        if self.is_available() {
+            Ok(())
+        } else {
+            Err(BbError::FlavorUnavailable(self))
+        }
+    }
+
+    /// The matching garaga flavor, if garaga can verify proofs of this flavor
+    pub fn garaga_flavor(self) -> Option<HonkFlavor> {
+        match self {
+            OracleHashFlavor::Poseidon2 => None,
+            OracleHashFlavor::Keccak => Some(HonkFlavor::KECCAK),
+            OracleHashFlavor::Starknet => Some(HonkFlavor::STARKNET),
+        }
+    }
+}
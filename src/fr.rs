@@ -0,0 +1,278 @@
+use std::fmt;
+use std::ops::Neg;
+use std::str::FromStr;
+
+use acir::{AcirField, FieldElement};
+use num_bigint::BigUint;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::BbError;
+
+/// BN254 scalar field modulus, big-endian
+const MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// BN254 scalar field element in canonical big-endian encoding
+///
+/// Values are always below the modulus, so barretenberg never reduces them
+/// silently.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fr([u8; 32]);
+
+impl Fr {
+    pub const ZERO: Fr = Fr([0; 32]);
+
+    /// Build a field element from 32 big-endian bytes, rejecting values at or above the modulus
+    pub fn from_be_bytes(bytes: [u8; 32]) -> Result<Self, BbError> {
+        if bytes >= MODULUS {
+            return Err(BbError::InvalidField(format!(
+                "0x{} is not below the BN254 modulus",
+                hex::encode(bytes)
+            )));
+        }
+        Ok(Fr(bytes))
+    }
+
+    /// Wrap bytes barretenberg produced, which are canonical by construction
+    pub(crate) fn from_be_bytes_unchecked(bytes: [u8; 32]) -> Self {
+        debug_assert!(bytes < MODULUS);
+        Fr(bytes)
+    }
+
+    fn from_biguint(value: &BigUint) -> Result<Self, BbError> {
+        let digits = value.to_bytes_be();
+        if digits.len() > 32 {
+            return Err(BbError::InvalidField(format!(
+                "{} is not below the BN254 modulus",
+                value
+            )));
+        }
+        let mut bytes = [0u8; 32];
+        bytes[32 - digits.len()..].copy_from_slice(&digits);
+        Fr::from_be_bytes(bytes)
+    }
+
+    /// Parse `digits` in `radix`, rejecting the `_` separators and `+` sign
+    /// `BigUint::parse_bytes` would accept
+    fn from_digits(s: &str, digits: &str, radix: u32, name: &str) -> Result<Self, BbError> {
+        let invalid = || BbError::InvalidField(format!("invalid {} string {:?}", name, s));
+        if !digits.chars().all(|c| c.is_digit(radix)) {
+            return Err(invalid());
+        }
+        let value = BigUint::parse_bytes(digits.as_bytes(), radix).ok_or_else(invalid)?;
+        Fr::from_biguint(&value)
+    }
+
+    /// Parse a hexadecimal string, with or without a `0x` prefix
+    pub fn from_hex(s: &str) -> Result<Self, BbError> {
+        Fr::from_digits(s, s.strip_prefix("0x").unwrap_or(s), 16, "hex")
+    }
+
+    /// Parse a decimal string
+    pub fn from_dec_str(s: &str) -> Result<Self, BbError> {
+        Fr::from_digits(s, s, 10, "decimal")
+    }
+
+    /// Parse like `from_str`, additionally mapping a leading `-` to `p - x`
+    pub fn from_signed_str(s: &str) -> Result<Self, BbError> {
+        match s.strip_prefix('-') {
+            Some(magnitude) => Ok(-Fr::from_str(magnitude)?),
+            None => Fr::from_str(s),
+        }
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+
+    pub fn as_be_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// `0x`-prefixed, zero-padded hexadecimal encoding
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.0))
+    }
+}
+
+impl FromStr for Fr {
+    type Err = BbError;
+
+    /// Hexadecimal, octal or binary if prefixed with `0x`, `0o` or `0b`, decimal otherwise
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (radix, digits, name) = if let Some(digits) = s.strip_prefix("0x") {
+            (16, digits, "hex")
+        } else if let Some(digits) = s.strip_prefix("0o") {
+            (8, digits, "octal")
+        } else if let Some(digits) = s.strip_prefix("0b") {
+            (2, digits, "binary")
+        } else {
+            (10, s, "decimal")
+        };
+        Fr::from_digits(s, digits, radix, name)
+    }
+}
+
+impl Neg for Fr {
+    type Output = Fr;
+
+    /// `p - x`, keeping zero canonical
+    fn neg(self) -> Fr {
+        if self == Fr::ZERO {
+            return self;
+        }
+        let modulus = BigUint::from_bytes_be(&MODULUS);
+        let value = BigUint::from_bytes_be(&self.0);
+        Fr::from_biguint(&(modulus - value)).expect("p - x is below the modulus")
+    }
+}
+
+impl fmt::Display for Fr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl From<u64> for Fr {
+    fn from(value: u64) -> Self {
+        Fr::from(value as u128)
+    }
+}
+
+impl From<u128> for Fr {
+    fn from(value: u128) -> Self {
+        let mut bytes = [0u8; 32];
+        bytes[16..].copy_from_slice(&value.to_be_bytes());
+        Fr(bytes)
+    }
+}
+
+impl TryFrom<Fr> for u128 {
+    type Error = BbError;
+
+    fn try_from(value: Fr) -> Result<Self, Self::Error> {
+        let (high, low) = value.0.split_at(16);
+        if high.iter().any(|&byte| byte != 0) {
+            return Err(BbError::InvalidField(format!(
+                "{} does not fit in a u128",
+                value
+            )));
+        }
+        Ok(u128::from_be_bytes(low.try_into().expect("16 bytes")))
+    }
+}
+
+impl TryFrom<Fr> for u64 {
+    type Error = BbError;
+
+    fn try_from(value: Fr) -> Result<Self, Self::Error> {
+        u128::try_from(value)
+            .ok()
+            .and_then(|value| u64::try_from(value).ok())
+            .ok_or_else(|| BbError::InvalidField(format!("{} does not fit in a u64", value)))
+    }
+}
+
+impl From<FieldElement> for Fr {
+    fn from(value: FieldElement) -> Self {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&value.to_be_bytes());
+        Fr(bytes)
+    }
+}
+
+impl From<Fr> for FieldElement {
+    fn from(value: Fr) -> Self {
+        FieldElement::from_be_bytes_reduce(&value.0)
+    }
+}
+
+impl TryFrom<[u8; 32]> for Fr {
+    type Error = BbError;
+
+    fn try_from(bytes: [u8; 32]) -> Result<Self, Self::Error> {
+        Fr::from_be_bytes(bytes)
+    }
+}
+
+/// Serialized as a `0x`-prefixed hex string, range checked on the way back in
+impl Serialize for Fr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for Fr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Fr::from_hex(&s).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_modulus() {
+        assert!(Fr::from_be_bytes(MODULUS).is_err());
+        assert!(
+            Fr::from_hex("0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001")
+                .is_err()
+        );
+
+        let mut below = MODULUS;
+        below[31] -= 1;
+        assert!(Fr::from_be_bytes(below).is_ok());
+    }
+
+    #[test]
+    fn parses_hex_and_decimal() {
+        assert_eq!(Fr::from_str("0x2a").unwrap(), Fr::from(42u64));
+        assert_eq!(Fr::from_str("42").unwrap(), Fr::from(42u64));
+        assert!(Fr::from_str("0xzz").is_err());
+        assert!(Fr::from_str("-1").is_err());
+    }
+
+    #[test]
+    fn parses_prefixes_and_negatives() {
+        assert_eq!(Fr::from_str("0b101010").unwrap(), Fr::from(42u64));
+        assert_eq!(Fr::from_str("0o52").unwrap(), Fr::from(42u64));
+        assert!(Fr::from_str("0b102").is_err());
+
+        let mut minus_one = MODULUS;
+        minus_one[31] -= 1;
+        assert_eq!(Fr::from_signed_str("-1").unwrap(), Fr(minus_one));
+        assert_eq!(Fr::from_signed_str("-0").unwrap(), Fr::ZERO);
+        assert!(Fr::from_signed_str("-").is_err());
+    }
+
+    #[test]
+    fn rejects_separators_and_signs() {
+        for s in ["0x2_a", "1__0", "+7", "-+7", "--7", " 7", "7 ", "0x", ""] {
+            assert!(Fr::from_signed_str(s).is_err(), "{:?} was accepted", s);
+        }
+        assert!(Fr::from_hex("2_a").is_err());
+        assert!(Fr::from_dec_str("+7").is_err());
+    }
+
+    #[test]
+    fn converts_to_integers() {
+        assert_eq!(u64::try_from(Fr::from(u64::MAX)).unwrap(), u64::MAX);
+        assert!(u64::try_from(Fr::from(u64::MAX as u128 + 1)).is_err());
+        assert_eq!(u128::try_from(Fr::from(u128::MAX)).unwrap(), u128::MAX);
+        assert!(matches!(
+            u128::try_from(-Fr::from(1u64)),
+            Err(BbError::InvalidField(_))
+        ));
+    }
+
+    #[test]
+    fn round_trips_field_element() {
+        let value = Fr::from(u128::MAX);
+        assert_eq!(Fr::from(FieldElement::from(value)), value);
+        assert_eq!(Fr::from_hex(&value.to_hex()).unwrap(), value);
+    }
+}
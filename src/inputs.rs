@@ -0,0 +1,226 @@
+//! Reading circuit inputs from `Prover.toml` or an equivalent JSON file
+//!
+//! Parsing is `noirc_abi`'s own, so values follow nargo's conventions; this
+//! module adds the line of the offending key to its errors.
+
+use std::fs;
+use std::path::Path;
+
+use acir::{native_types::WitnessMap, FieldElement};
+use noirc_abi::{errors::InputParserError, input_parser::Format, Abi};
+
+use crate::{
+    abi::{encode_inputs, invalid_input, InputMap},
+    error::BbError,
+};
+
+/// Key nargo uses for the return value, which is not an input
+const RETURN_KEY: &str = "return";
+
+/// Syntax of an inputs file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Toml,
+    Json,
+}
+
+impl InputFormat {
+    /// Pick the format from a `.toml` or `.json` extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(InputFormat::Toml),
+            "json" => Some(InputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Map a `noirc_abi` parser error to the input it names
+fn parser_error(error: InputParserError) -> BbError {
+    let path = match &error {
+        InputParserError::ParseStr { arg_name, .. }
+        | InputParserError::InputUnderflowsMinimum { arg_name, .. }
+        | InputParserError::InputOverflowsMaximum { arg_name, .. }
+        | InputParserError::InputExceedsFieldModulus { arg_name, .. }
+        | InputParserError::MissingArgument(arg_name) => arg_name.clone(),
+        _ => return BbError::InputSyntax(error.to_string()),
+    };
+    invalid_input(&path, error.to_string())
+}
+
+/// Line (1-based) where the key at `path` is written
+///
+/// Walks the path's names in order, so `point.x` finds the `x` after `point`,
+/// whether `point` is a table header, an inline table or a JSON object.
+fn line_of(source: &str, format: InputFormat, path: &str) -> Option<usize> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut line = 0;
+    for name in path
+        .split(['.', '['])
+        .filter(|name| !name.is_empty() && !name.ends_with(']'))
+        // Tuple indices are positional, not keys
+        .filter(|name| !name.bytes().all(|b| b.is_ascii_digit()))
+    {
+        line += lines[line..].iter().position(|text| match format {
+            InputFormat::Toml => {
+                let text = text.trim_start();
+                let header = text.trim_start_matches('[');
+                (text.starts_with('[')
+                    && header
+                        .split(']')
+                        .next()
+                        .is_some_and(|header| header.rsplit('.').next() == Some(name)))
+                    || text
+                        .split(['{', ','])
+                        .any(|part| is_assignment(part.trim_start(), name))
+            }
+            InputFormat::Json => text
+                .match_indices(&format!("\"{}\"", name))
+                .any(|(index, key)| text[index + key.len()..].trim_start().starts_with(':')),
+        })?;
+    }
+    Some(line + 1)
+}
+
+/// Whether `text` starts with a TOML assignment to `name`, bare or quoted
+fn is_assignment(text: &str, name: &str) -> bool {
+    let key = text.trim_start_matches(['"', '\'']);
+    key.strip_prefix(name).is_some_and(|rest| {
+        rest.trim_start_matches(['"', '\''])
+            .trim_start()
+            .starts_with('=')
+    })
+}
+
+/// Attach the source line to an input error
+fn with_line(error: BbError, source: &str, format: InputFormat) -> BbError {
+    match error {
+        BbError::InvalidInput { path, message, .. } => BbError::InvalidInput {
+            line: line_of(source, format, &path),
+            path,
+            message,
+        },
+        error => error,
+    }
+}
+
+/// Parse an inputs file against the circuit ABI
+///
+/// # Arguments
+///
+/// * source: Contents of `Prover.toml` or an equivalent JSON file
+/// * format: The syntax of `source`
+/// * abi: The ABI of the circuit
+///
+/// # Returns
+///
+/// The named inputs, or `BbError::InvalidInput` naming the offending key and line
+pub fn parse_inputs(source: &str, format: InputFormat, abi: &Abi) -> Result<InputMap, BbError> {
+    let parser = match format {
+        InputFormat::Toml => Format::Toml,
+        InputFormat::Json => Format::Json,
+    };
+    let mut inputs = parser
+        .parse(source, abi)
+        .map_err(|e| with_line(parser_error(e), source, format))?;
+    inputs.remove(RETURN_KEY);
+    Ok(inputs)
+}
+
+/// Parse an inputs file and encode it into the initial witness map
+pub fn inputs_to_witness_map(
+    source: &str,
+    format: InputFormat,
+    abi: &Abi,
+) -> Result<WitnessMap<FieldElement>, BbError> {
+    parse_inputs(source, format, abi)
+        .and_then(|inputs| encode_inputs(abi, &inputs))
+        .map_err(|e| with_line(e, source, format))
+}
+
+/// Read `Prover.toml` (or a `.json` inputs file) and encode it into the initial witness map
+pub fn read_inputs_file(
+    path: impl AsRef<Path>,
+    abi: &Abi,
+) -> Result<WitnessMap<FieldElement>, BbError> {
+    let path = path.as_ref();
+    let format = InputFormat::from_path(path).ok_or_else(|| {
+        BbError::InputSyntax(format!(
+            "{}: expected a .toml or .json file",
+            path.display()
+        ))
+    })?;
+    let source = fs::read_to_string(path)
+        .map_err(|e| BbError::InputSyntax(format!("{}: {}", path.display(), e)))?;
+    inputs_to_witness_map(&source, format, abi)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acir::{native_types::Witness, AcirField};
+    use noirc_abi::{AbiParameter, AbiType, AbiVisibility, Sign};
+
+    use super::*;
+
+    fn abi() -> Abi {
+        let parameter = |name: &str, typ| AbiParameter {
+            name: name.to_string(),
+            typ,
+            visibility: AbiVisibility::Private,
+        };
+        Abi {
+            parameters: vec![
+                parameter("x", AbiType::Field),
+                parameter(
+                    "point",
+                    AbiType::Struct {
+                        path: "Point".to_string(),
+                        fields: vec![
+                            (
+                                "a".to_string(),
+                                AbiType::Integer {
+                                    sign: Sign::Unsigned,
+                                    width: 8,
+                                },
+                            ),
+                            ("b".to_string(), AbiType::Boolean),
+                        ],
+                    },
+                ),
+            ],
+            return_type: None,
+            error_types: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn reads_toml_and_json() {
+        let toml = "x = \"0x10\"\n\n[point]\na = 3\nb = true\n";
+        let json = r#"{ "x": 16, "point": { "a": "3", "b": true } }"#;
+        for (source, format) in [(toml, InputFormat::Toml), (json, InputFormat::Json)] {
+            let witness = inputs_to_witness_map(source, format, &abi()).unwrap();
+            assert_eq!(witness[&Witness(0)].to_u128(), 16);
+            assert_eq!(witness[&Witness(1)].to_u128(), 3);
+            assert_eq!(witness[&Witness(2)].to_u128(), 1);
+        }
+    }
+
+    #[test]
+    fn points_at_key_and_line() {
+        let toml = "x = 1\n\n[point]\na = 300\nb = false\n";
+        let err = inputs_to_witness_map(toml, InputFormat::Toml, &abi()).unwrap_err();
+        assert!(matches!(
+            err,
+            BbError::InvalidInput { ref path, line: Some(4), .. } if path == "point.a"
+        ));
+
+        let json = "{\n  \"x\": \"0xzz\",\n  \"point\": { \"a\": 1, \"b\": true }\n}";
+        let err = inputs_to_witness_map(json, InputFormat::Json, &abi()).unwrap_err();
+        assert!(matches!(
+            err,
+            BbError::InvalidInput { ref path, line: Some(2), .. } if path == "x"
+        ));
+    }
+}
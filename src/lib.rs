@@ -1,34 +1,34 @@
-#![allow(non_upper_case_globals)]
-#![allow(non_camel_case_types)]
-#![allow(non_snake_case)]
-
+pub mod abi;
+pub mod aes128;
+pub mod artifact;
 pub mod barretenberg;
+mod buffer;
 pub mod circuits;
+pub mod error;
 pub mod execute;
+pub mod flavor;
+pub mod fr;
+pub mod inputs;
+pub mod oracles;
 pub mod prove;
+pub mod schnorr;
+pub mod verify;
 pub mod witness;
 
-// Load FFI bindings into a module, defining the same aliases
-// expected by the generated bindings.rs.
-pub mod bindgen {
-    // Aliases required by bindings.rs:
-    pub type out_buf = *mut u8;
-    pub type in_buf = *const u8;
-    pub type vec_in_buf = *const u8;
-    pub type out_buf32 = *mut u8;
-
-    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
-}
+use barretenberg_sys as bindgen;
+use bindgen::in_ptr;
+use buffer::BbBuffer;
 
 // Re-export some commonly used types
-pub use bindgen::{in_ptr, out_str_buf, vec_out_buf};
+pub use error::BbError;
+pub use fr::Fr;
 
 /// encoding helpers:
-fn encode_vector_of_fr(fr_list: &[[u8; 32]]) -> Vec<u8> {
+fn encode_vector_of_fr(fr_list: &[Fr]) -> Vec<u8> {
     let mut buffer = Vec::with_capacity(4 + fr_list.len() * 32);
     buffer.extend_from_slice(&(fr_list.len() as u32).to_be_bytes());
     for fr in fr_list {
-        buffer.extend_from_slice(fr);
+        buffer.extend_from_slice(fr.as_be_bytes());
     }
     buffer
 }
@@ -40,27 +40,27 @@ fn encode_raw_buffer(data: &[u8]) -> Vec<u8> {
     buffer
 }
 
-/// Parse a `vec_out_buf` (length-prefixed array of 32-byte elements)
-unsafe fn parse_vec_out_buf(ptr: vec_out_buf) -> Vec<[u8; 32]> {
-    if ptr.is_null() || (*ptr).is_null() {
-        return Vec::new();
-    }
-    let data_ptr = *ptr;
-    let len_bytes = std::slice::from_raw_parts(data_ptr, 4);
-    let len = u32::from_be_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]) as usize;
-    let mut result = Vec::with_capacity(len);
-    for i in 0..len {
-        let offset = 4 + i * 32;
-        let mut element = [0u8; 32];
-        element.copy_from_slice(std::slice::from_raw_parts(data_ptr.add(offset), 32));
-        result.push(element);
-    }
-    result
+/// Split a flat output buffer written by barretenberg into field elements
+fn decode_fr_chunks(raw: &[u8]) -> Vec<Fr> {
+    raw.chunks_exact(32)
+        .map(|chunk| {
+            let mut arr = [0u8; 32];
+            arr.copy_from_slice(chunk);
+            Fr::from_be_bytes_unchecked(arr)
+        })
+        .collect()
 }
 
-/// Blake2s hash of field elements
-pub fn blake2s_safe(input: &[[u8; 32]]) -> [u8; 32] {
-    let buf = encode_vector_of_fr(input);
+/// Field elements as one length-prefixed byte buffer, the `std::vector<uint8_t>`
+/// barretenberg's blake2s bindings read
+fn encode_fr_bytes(fr_list: &[Fr]) -> Vec<u8> {
+    let bytes: Vec<u8> = fr_list.iter().flat_map(|fr| *fr.as_be_bytes()).collect();
+    encode_raw_buffer(&bytes)
+}
+
+/// Blake2s hash of field elements (the digest is not reduced into the field)
+pub fn blake2s_safe(input: &[Fr]) -> [u8; 32] {
+    let buf = encode_fr_bytes(input);
     let mut out = [0u8; 32];
     unsafe {
         bindgen::blake2s(buf.as_ptr(), out.as_mut_ptr());
@@ -69,27 +69,27 @@ pub fn blake2s_safe(input: &[[u8; 32]]) -> [u8; 32] {
 }
 
 /// Blake2s → field
-pub fn blake2s_to_field_safe(input: &[[u8; 32]]) -> [u8; 32] {
-    let buf = encode_vector_of_fr(input);
+pub fn blake2s_to_field_safe(input: &[Fr]) -> Fr {
+    let buf = encode_fr_bytes(input);
     let mut out = [0u8; 32];
     unsafe {
         bindgen::blake2s_to_field_(buf.as_ptr(), out.as_mut_ptr());
     }
-    out
+    Fr::from_be_bytes_unchecked(out)
 }
 
 /// Poseidon2 hash of field elements
-pub fn poseidon2_hash_safe(input: &[[u8; 32]]) -> [u8; 32] {
+pub fn poseidon2_hash_safe(input: &[Fr]) -> Fr {
     let buf = encode_vector_of_fr(input);
     let mut out = [0u8; 32];
     unsafe {
         bindgen::poseidon2_hash(buf.as_ptr(), out.as_mut_ptr());
     }
-    out
+    Fr::from_be_bytes_unchecked(out)
 }
 
 /// Poseidon2 hashes of multiple element-sets
-pub fn poseidon2_hashes_safe(inputs: &[Vec<[u8; 32]>]) -> Vec<[u8; 32]> {
+pub fn poseidon2_hashes_safe(inputs: &[Vec<Fr>]) -> Vec<Fr> {
     let mut buf = Vec::new();
     buf.extend_from_slice(&(inputs.len() as u32).to_be_bytes());
     for set in inputs {
@@ -99,50 +99,46 @@ pub fn poseidon2_hashes_safe(inputs: &[Vec<[u8; 32]>]) -> Vec<[u8; 32]> {
     unsafe {
         bindgen::poseidon2_hashes(buf.as_ptr(), raw.as_mut_ptr());
     }
-    raw.chunks_exact(32)
-        .map(|c| {
-            let mut a = [0u8; 32];
-            a.copy_from_slice(c);
-            a
-        })
-        .collect()
+    decode_fr_chunks(&raw)
 }
 
-/// Poseidon2 permutation → Vec<[u8;32]>
-pub fn poseidon2_permutation_safe(input: &[[u8; 32]]) -> Vec<[u8; 32]> {
+/// Poseidon2 permutation → Vec<Fr>
+pub fn poseidon2_permutation_safe(input: &[Fr]) -> Result<Vec<Fr>, BbError> {
     let buf = encode_vector_of_fr(input);
-    unsafe {
-        let mut raw_ptr: *mut u8 = std::ptr::null_mut();
-        bindgen::poseidon2_permutation(buf.as_ptr(), &mut raw_ptr);
-        parse_vec_out_buf(&mut raw_ptr)
-    }
+    let out =
+        unsafe { BbBuffer::from_out_ptr(|out| bindgen::poseidon2_permutation(buf.as_ptr(), out))? };
+    Ok(out
+        .as_fields()
+        .iter()
+        .map(|field| Fr::from_be_bytes_unchecked(*field))
+        .collect())
 }
 
 /// Poseidon2 accumulate hash
-pub fn poseidon2_accumulate_safe(input: &[[u8; 32]]) -> [u8; 32] {
+pub fn poseidon2_accumulate_safe(input: &[Fr]) -> Fr {
     let buf = encode_vector_of_fr(input);
     let mut out = [0u8; 32];
     unsafe {
         bindgen::poseidon2_hash_accumulate(buf.as_ptr(), out.as_mut_ptr());
     }
-    out
+    Fr::from_be_bytes_unchecked(out)
 }
 
 /// Pedersen hash of field elements
-pub fn pedersen_hash_safe(input: &[[u8; 32]], idx: u32) -> [u8; 32] {
+pub fn pedersen_hash_safe(input: &[Fr], idx: u32) -> Fr {
     let buf = encode_vector_of_fr(input);
     let mut out = [0u8; 32];
     unsafe {
         bindgen::pedersen_hash(buf.as_ptr(), &idx as *const u32, out.as_mut_ptr());
     }
-    out
+    Fr::from_be_bytes_unchecked(out)
 }
 
 /// Pedersen hashes of multiple element-sets
-pub fn pedersen_hashes_safe(input: &[[u8; 32]], idx: u32) -> Vec<[u8; 32]> {
-    // 1) wejście zakodowane z 4-bajtowym prefiksem dłużści:
+pub fn pedersen_hashes_safe(input: &[Fr], idx: u32) -> Vec<Fr> {
+    // 1) wejście zakodowane z 4-bajtowym prefiksem dłużści:
     let buf = encode_vector_of_fr(input);
-    // 2) rezerwujemy miejsce na wyjście: 32 bajty * liczba elementów
+    // 2) rezerwujemy miejsce na wyjście: 32 bajty * liczba elementów
     let mut raw = vec![0u8; 32 * input.len()];
 
     unsafe {
@@ -155,33 +151,28 @@ pub fn pedersen_hashes_safe(input: &[[u8; 32]], idx: u32) -> Vec<[u8; 32]> {
     }
 
     // 4) dzielimy płaski Vec<u8> na kawałki po 32 bajty
-    raw.chunks_exact(32)
-        .map(|chunk| {
-            let mut arr = [0u8; 32];
-            arr.copy_from_slice(chunk);
-            arr
-        })
-        .collect()
+    decode_fr_chunks(&raw)
 }
 
 /// Pedersen hash of raw bytes
-pub fn pedersen_hash_buffer_safe(input: &[u8], idx: u32) -> [u8; 32] {
+pub fn pedersen_hash_buffer_safe(input: &[u8], idx: u32) -> Fr {
     let buf = encode_raw_buffer(input);
     let mut out = [0u8; 32];
     unsafe {
         bindgen::pedersen_hash_buffer(buf.as_ptr(), &idx as *const u32, out.as_mut_ptr());
     }
-    out
+    Fr::from_be_bytes_unchecked(out)
 }
 
 /// Pedersen commitment (x,y)
-pub fn pedersen_commit_safe(input: &[[u8; 32]], ctx: u32) -> [u8; 64] {
+pub fn pedersen_commit_safe(input: &[Fr], ctx: u32) -> (Fr, Fr) {
     let buf = encode_vector_of_fr(input);
     let mut out = [0u8; 64];
     unsafe {
         bindgen::pedersen_commit(buf.as_ptr(), &ctx as *const u32, out.as_mut_ptr());
     }
-    out
+    let mut points = decode_fr_chunks(&out).into_iter();
+    (points.next().unwrap(), points.next().unwrap())
 }
 
 /// Initialize SRS (Structured Reference String)
@@ -256,25 +247,19 @@ pub fn acir_init_vk_safe(ptr: in_ptr) {
 }
 
 /// ACIR: get verification key → Vec<u8>
-pub fn acir_get_vk_safe(ptr: in_ptr) -> Vec<u8> {
-    unsafe {
-        let mut out_raw: *mut u8 = std::ptr::null_mut();
-        bindgen::acir_get_verification_key(ptr, &mut out_raw);
-        // assume length-prefixed
-        let v = parse_vec_out_buf(&mut out_raw);
-        v.into_iter().flatten().collect()
-    }
+pub fn acir_get_vk_safe(ptr: in_ptr) -> Result<Vec<u8>, BbError> {
+    let out =
+        unsafe { BbBuffer::from_out_ptr(|out| bindgen::acir_get_verification_key(ptr, out))? };
+    Ok(out.as_bytes().to_vec())
 }
 
 /// ACIR: get proving key → Vec<u8>
-pub fn acir_get_pk_safe(ptr: in_ptr, vec: &[u8], rec: bool) -> Vec<u8> {
+pub fn acir_get_pk_safe(ptr: in_ptr, vec: &[u8], rec: bool) -> Result<Vec<u8>, BbError> {
     let buf = encode_raw_buffer(vec);
-    unsafe {
-        let mut out_raw: *mut u8 = std::ptr::null_mut();
-        bindgen::acir_get_proving_key(ptr, buf.as_ptr(), &rec, &mut out_raw);
-        let v = parse_vec_out_buf(&mut out_raw);
-        v.into_iter().flatten().collect()
-    }
+    let out = unsafe {
+        BbBuffer::from_out_ptr(|out| bindgen::acir_get_proving_key(ptr, buf.as_ptr(), &rec, out))?
+    };
+    Ok(out.as_bytes().to_vec())
 }
 
 /// ACIR: verify proof
@@ -288,47 +273,43 @@ pub fn acir_verify_proof_safe(ptr: in_ptr, proof: &[u8]) -> bool {
 }
 
 /// ACIR: serialize proof into fields
+///
+/// barretenberg reads `num_inner` as a big-endian uint32, like every other integer input
 pub fn acir_serialize_proof_fields_safe(
     ptr: in_ptr,
     proof: &[u8],
     num_inner: u32,
-) -> Vec<[u8; 32]> {
+) -> Result<Vec<[u8; 32]>, BbError> {
     let buf = encode_raw_buffer(proof);
-    unsafe {
-        let mut raw_ptr: *mut u8 = std::ptr::null_mut();
-        bindgen::acir_serialize_proof_into_fields(
-            ptr,
-            buf.as_ptr(),
-            &num_inner as *const u32,
-            &mut raw_ptr,
-        );
-        parse_vec_out_buf(&mut raw_ptr)
-    }
+    let out = unsafe {
+        BbBuffer::from_out_ptr(|out| {
+            bindgen::acir_serialize_proof_into_fields(ptr, buf.as_ptr(), &num_inner.to_be(), out)
+        })?
+    };
+    Ok(out.as_fields().to_vec())
 }
 
 /// ACIR: serialize VK into fields + hash
-pub fn acir_serialize_vk_fields_safe(ptr: in_ptr) -> (Vec<[u8; 32]>, [u8; 32]) {
-    unsafe {
-        let mut raw_ptr: *mut u8 = std::ptr::null_mut();
-        let mut hash = [0u8; 32];
-
-        bindgen::acir_serialize_verification_key_into_fields(ptr, &mut raw_ptr, hash.as_mut_ptr());
-
-        let fields = parse_vec_out_buf(&mut raw_ptr);
-        (fields, hash)
-    }
+pub fn acir_serialize_vk_fields_safe(ptr: in_ptr) -> Result<(Vec<[u8; 32]>, [u8; 32]), BbError> {
+    let mut hash = [0u8; 32];
+    let out = unsafe {
+        BbBuffer::from_out_ptr(|out| {
+            bindgen::acir_serialize_verification_key_into_fields(ptr, out, hash.as_mut_ptr())
+        })?
+    };
+    Ok((out.as_fields().to_vec(), hash))
 }
 
 /// ACIR: prove UltraHonk → Vec<u8>
-pub fn acir_prove_ultra_honk_safe(cs: &[u8], wit: &[u8]) -> Vec<u8> {
+pub fn acir_prove_ultra_honk_safe(cs: &[u8], wit: &[u8]) -> Result<Vec<u8>, BbError> {
     let cs_buf = encode_raw_buffer(cs);
     let wit_buf = encode_raw_buffer(wit);
-    unsafe {
-        let mut out_raw: *mut u8 = std::ptr::null_mut();
-        bindgen::acir_prove_ultra_honk(cs_buf.as_ptr(), wit_buf.as_ptr(), &mut out_raw);
-        let v = parse_vec_out_buf(&mut out_raw);
-        v.into_iter().flatten().collect()
-    }
+    let out = unsafe {
+        BbBuffer::from_out_ptr(|out| {
+            bindgen::acir_prove_ultra_honk(cs_buf.as_ptr(), wit_buf.as_ptr(), out)
+        })?
+    };
+    Ok(out.as_bytes().to_vec())
 }
 
 /// ACIR: verify UltraHonk proof
@@ -342,86 +323,303 @@ pub fn acir_verify_ultra_honk_safe(proof: &[u8], vk: &[u8]) -> bool {
     ok
 }
 
-/// ACIR: write VK UltraHonk → Vec<u8>
-pub fn acir_write_vk_ultra_honk_safe(vec: &[u8]) -> Vec<u8> {
-    let buf = encode_raw_buffer(vec);
+/// ACIR: verify UltraKeccakHonk proof
+pub fn acir_verify_ultra_keccak_honk_safe(proof: &[u8], vk: &[u8]) -> bool {
+    let p_buf = encode_raw_buffer(proof);
+    let v_buf = encode_raw_buffer(vk);
+    let mut ok = false;
     unsafe {
-        let mut out_raw: *mut u8 = std::ptr::null_mut();
-        bindgen::acir_write_vk_ultra_honk(buf.as_ptr(), &mut out_raw);
-        let v = parse_vec_out_buf(&mut out_raw);
-        v.into_iter().flatten().collect()
+        bindgen::acir_verify_ultra_keccak_honk(p_buf.as_ptr(), v_buf.as_ptr(), &mut ok);
     }
+    ok
 }
 
-/// ACIR: proof as fields
-pub fn acir_proof_as_fields_ultra_honk_safe(proof: &[u8]) -> Vec<[u8; 32]> {
-    let buf = encode_raw_buffer(proof);
+/// ACIR: verify UltraStarknetHonk proof
+#[cfg(bb_starknet)]
+pub fn acir_verify_ultra_starknet_honk_safe(proof: &[u8], vk: &[u8]) -> bool {
+    let p_buf = encode_raw_buffer(proof);
+    let v_buf = encode_raw_buffer(vk);
+    let mut ok = false;
     unsafe {
-        let mut raw_ptr: *mut u8 = std::ptr::null_mut();
-        bindgen::acir_proof_as_fields_ultra_honk(buf.as_ptr(), &mut raw_ptr);
-        parse_vec_out_buf(&mut raw_ptr)
+        bindgen::acir_verify_ultra_starknet_honk(p_buf.as_ptr(), v_buf.as_ptr(), &mut ok);
     }
+    ok
+}
+
+/// ACIR: write VK UltraHonk → Vec<u8>
+pub fn acir_write_vk_ultra_honk_safe(vec: &[u8]) -> Result<Vec<u8>, BbError> {
+    let buf = encode_raw_buffer(vec);
+    let out = unsafe {
+        BbBuffer::from_out_ptr(|out| bindgen::acir_write_vk_ultra_honk(buf.as_ptr(), out))?
+    };
+    Ok(out.as_bytes().to_vec())
+}
+
+/// ACIR: write VK UltraKeccakHonk → Vec<u8>
+pub fn acir_write_vk_ultra_keccak_honk_safe(vec: &[u8]) -> Result<Vec<u8>, BbError> {
+    let buf = encode_raw_buffer(vec);
+    let out = unsafe {
+        BbBuffer::from_out_ptr(|out| bindgen::acir_write_vk_ultra_keccak_honk(buf.as_ptr(), out))?
+    };
+    Ok(out.as_bytes().to_vec())
+}
+
+/// ACIR: write VK UltraStarknetHonk → Vec<u8>
+#[cfg(bb_starknet)]
+pub fn acir_write_vk_ultra_starknet_honk_safe(vec: &[u8]) -> Result<Vec<u8>, BbError> {
+    let buf = encode_raw_buffer(vec);
+    let out = unsafe {
+        BbBuffer::from_out_ptr(|out| bindgen::acir_write_vk_ultra_starknet_honk(buf.as_ptr(), out))?
+    };
+    Ok(out.as_bytes().to_vec())
+}
+
+/// ACIR: proof as fields
+pub fn acir_proof_as_fields_ultra_honk_safe(proof: &[u8]) -> Result<Vec<[u8; 32]>, BbError> {
+    let buf = encode_raw_buffer(proof);
+    let out = unsafe {
+        BbBuffer::from_out_ptr(|out| bindgen::acir_proof_as_fields_ultra_honk(buf.as_ptr(), out))?
+    };
+    Ok(out.as_fields().to_vec())
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
 
+    use acir::{native_types::Witness, AcirField, FieldElement};
     use garaga_rs::calldata::full_proof_with_hints::honk::{
-        get_honk_calldata, HonkFlavor, HonkProof, HonkVerificationKey,
+        get_honk_calldata, HonkProof, HonkVerificationKey,
     };
 
     use crate::{
-        barretenberg::{srs::setup_srs_from_bytecode, utils::compute_subgroup_size},
+        aes128::{decrypt_cbc, encrypt_cbc},
+        barretenberg::{
+            srs::setup_srs_from_bytecode,
+            utils::{compute_subgroup_size, get_honk_verification_key},
+        },
         circuits::decode_circuit,
+        execute::execute,
+        flavor::OracleHashFlavor,
+        oracles::PrintOutput,
         prove::{prove_ultra_honk, ProofResponse},
-        witness::from_vec_to_witness_map,
+        schnorr::{
+            multisig::{MultisigAggregator, MultisigSigner, RoundOneCommitment},
+            PrivateKey, Signature,
+        },
+        verify::{verify_ultra_honk, VerificationKey},
+        witness::{from_vec_str_to_witness_map, from_vec_to_witness_map},
     };
 
     use super::*;
     const FR: [u8; 32] = [1; 32];
     const MSG: &[u8] = b"hello";
 
+    fn fr() -> Fr {
+        Fr::from_be_bytes(FR).unwrap()
+    }
+
+    /// Reference digest: Python's `hashlib.blake2s(bytes([1] * 64))`
+    const BLAKE2S_TWO_FR: &str = "dc3c4c7e77f743a2625e771cf71247d0a74821553b38600d0943316d5ff6987f";
+
     #[test]
     fn t_blake2s() {
-        assert_ne!(blake2s_safe(&[FR]), [0; 32]);
+        assert_eq!(hex::encode(blake2s_safe(&[fr(), fr()])), BLAKE2S_TWO_FR);
     }
     #[test]
     fn t_blake2f() {
-        assert_ne!(blake2s_to_field_safe(&[FR]), [0; 32]);
+        // The same digest reduced modulo the BN254 scalar field
+        assert_eq!(
+            blake2s_to_field_safe(&[fr(), fr()]),
+            Fr::from_hex("0x1aab12b2f330c2fb811d6042f10ce65c0678803354529dc7f9bb5b1d9ff6987b")
+                .unwrap()
+        );
     }
     #[test]
     fn t_poseidon() {
-        assert_ne!(poseidon2_hash_safe(&[FR, FR]), [0; 32]);
+        assert_ne!(poseidon2_hash_safe(&[fr(), fr()]), Fr::ZERO);
     }
     #[test]
     fn t_poseidon_hashes() {
-        assert!(!poseidon2_hashes_safe(&vec![vec![FR]]).is_empty());
+        assert!(!poseidon2_hashes_safe(&vec![vec![fr()]]).is_empty());
     }
     #[test]
     fn t_poseidon_perm() {
-        assert!(!poseidon2_permutation_safe(&[FR]).is_empty());
+        assert!(!poseidon2_permutation_safe(&[fr()]).unwrap().is_empty());
     }
     #[test]
     fn t_poseidon_acc() {
-        assert_ne!(poseidon2_accumulate_safe(&[FR]), [0; 32]);
+        assert_ne!(poseidon2_accumulate_safe(&[fr()]), Fr::ZERO);
     }
 
     #[test]
     fn t_pedersen_hash() {
-        assert_ne!(pedersen_hash_safe(&[FR], 0), [0; 32]);
+        assert_ne!(pedersen_hash_safe(&[fr()], 0), Fr::ZERO);
     }
     #[test]
     fn t_pedersen_hashes() {
-        assert!(!pedersen_hashes_safe(&[FR], 0).is_empty());
+        assert!(!pedersen_hashes_safe(&[fr()], 0).is_empty());
     }
     #[test]
     fn t_pedersen_buf() {
-        assert_ne!(pedersen_hash_buffer_safe(MSG, 0), [0; 32]);
+        assert_ne!(pedersen_hash_buffer_safe(MSG, 0), Fr::ZERO);
     }
     #[test]
     fn t_pedersen_commit() {
-        assert_ne!(pedersen_commit_safe(&[FR], 0), [0; 64]);
+        assert_ne!(pedersen_commit_safe(&[fr()], 0), (Fr::ZERO, Fr::ZERO));
+    }
+
+    #[test]
+    fn t_schnorr_public_key_of_one_is_generator() {
+        let public_key = PrivateKey::from_bytes(Fr::from(1u64).to_be_bytes())
+            .unwrap()
+            .public_key();
+        assert_eq!(public_key.x, Fr::from(1u64));
+        assert_eq!(
+            public_key.y,
+            Fr::from_hex("0x0000000000000002cf135e7506a45d632d270d45f1181294833fc48d823f272c")
+                .unwrap()
+        );
+    }
+    #[test]
+    fn t_schnorr_sign_verify() {
+        let private_key = PrivateKey::from_bytes(FR).unwrap();
+        let public_key = private_key.public_key();
+        let signature = private_key.sign(MSG);
+        assert!(public_key.verify(MSG, &signature));
+        assert!(!public_key.verify(b"hellO", &signature));
+        assert_eq!(Signature::from_bytes(signature.to_bytes()), signature);
+    }
+    /// Replays the check the noir-lang `schnorr` library's `verify_signature`
+    /// makes in-circuit: `R = s*G + e*pk` over Grumpkin, then
+    /// `blake2s(pedersen_hash([R.x, pk.x, pk.y]) || message)` must equal `e`
+    #[test]
+    fn t_schnorr_signature_passes_noir_verification() {
+        use acvm::BlackBoxFunctionSolver;
+        use bn254_blackbox_solver::Bn254BlackBoxSolver;
+        use num_bigint::BigUint;
+
+        // Grumpkin's scalar field is BN254's base field
+        let grumpkin_order = BigUint::parse_bytes(
+            b"21888242871839275222246405745257275088696311157297823662689037894645226208583",
+            10,
+        )
+        .unwrap();
+        // `EmbeddedCurveScalar::from_bytes`: big-endian, split into 128-bit limbs
+        let limbs = |bytes: &[u8; 32]| {
+            let mut reduced = [0u8; 32];
+            let digits = (BigUint::from_bytes_be(bytes) % &grumpkin_order).to_bytes_be();
+            reduced[32 - digits.len()..].copy_from_slice(&digits);
+            (
+                FieldElement::from_be_bytes_reduce(&reduced[16..]),
+                FieldElement::from_be_bytes_reduce(&reduced[..16]),
+            )
+        };
+
+        let private_key = PrivateKey::from_bytes(FR).unwrap();
+        let public_key = private_key.public_key();
+        let signature = Signature::from_bytes(private_key.sign(MSG).to_bytes());
+        let pk_x = FieldElement::from_be_bytes_reduce(public_key.x.as_be_bytes());
+        let pk_y = FieldElement::from_be_bytes_reduce(public_key.y.as_be_bytes());
+
+        let generator_y =
+            Fr::from_hex("0x0000000000000002cf135e7506a45d632d270d45f1181294833fc48d823f272c")
+                .unwrap();
+        let (s_lo, s_hi) = limbs(&signature.s);
+        let (e_lo, e_hi) = limbs(&signature.e);
+        let (r_x, _, r_infinite) = Bn254BlackBoxSolver::default()
+            .multi_scalar_mul(
+                &[
+                    FieldElement::one(),
+                    FieldElement::from_be_bytes_reduce(generator_y.as_be_bytes()),
+                    FieldElement::zero(),
+                    pk_x,
+                    pk_y,
+                    FieldElement::zero(),
+                ],
+                &[s_lo, e_lo],
+                &[s_hi, e_hi],
+            )
+            .unwrap();
+        assert!(r_infinite.is_zero());
+
+        let r_x = Fr::from_be_bytes_unchecked(r_x.to_be_bytes().try_into().unwrap());
+        let challenge = pedersen_hash_safe(&[r_x, public_key.x, public_key.y], 0);
+        let mut hash_input = challenge.to_be_bytes().to_vec();
+        hash_input.extend_from_slice(MSG);
+        let buf = encode_raw_buffer(&hash_input);
+        let mut e = [0u8; 32];
+        unsafe {
+            bindgen::blake2s(buf.as_ptr(), e.as_mut_ptr());
+        }
+        assert_eq!(e, signature.e);
+    }
+    #[test]
+    fn t_schnorr_rejects_zero_key() {
+        assert!(PrivateKey::from_bytes([0; 32]).is_err());
+    }
+
+    #[test]
+    fn t_schnorr_multisig() {
+        let signers: Vec<MultisigSigner> = (1..=3u64)
+            .map(|i| {
+                MultisigSigner::new(PrivateKey::from_bytes(Fr::from(i).to_be_bytes()).unwrap())
+            })
+            .collect();
+        let public_keys: Vec<_> = signers.iter().map(MultisigSigner::public_key).collect();
+        let aggregator = MultisigAggregator::new(public_keys.clone()).unwrap();
+
+        let (round_one, commitments): (Vec<_>, Vec<_>) =
+            signers.iter().map(MultisigSigner::round_one).unzip();
+
+        // Commitments travel between parties serialized
+        let commitments: Vec<RoundOneCommitment> = commitments
+            .iter()
+            .map(|c| bincode::deserialize(&bincode::serialize(c).unwrap()).unwrap())
+            .collect();
+
+        let shares: Vec<_> = round_one
+            .into_iter()
+            .map(|signer| signer.round_two(MSG, &public_keys, &commitments).unwrap())
+            .collect();
+
+        let signature = aggregator.combine(MSG, &commitments, &shares).unwrap();
+        assert!(aggregator.combined_public_key().verify(MSG, &signature));
+
+        assert!(aggregator.combine(MSG, &commitments, &shares[..2]).is_err());
+    }
+
+    #[test]
+    fn t_aes128_matches_noir_black_box() {
+        // Inputs of Noir's aes128_encrypt test program
+        let key = *b"0000000000000000";
+        let iv = *b"0000000000000000";
+        let plaintext = b"kevlarkevlarkevlarkevlar";
+        let ciphertext = encrypt_cbc(&key, &iv, plaintext).unwrap();
+        assert_eq!(
+            hex::encode(&ciphertext),
+            "a0cf95b68c2f5defd02f9577eefda8c009c84dd3ddf20d27a7269a7f4c48e8ca"
+        );
+        assert_eq!(decrypt_cbc(&key, &iv, &ciphertext).unwrap(), plaintext);
+    }
+    #[test]
+    fn t_aes128_padding() {
+        let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let iv = [0u8; 16];
+
+        // A full block of padding is appended to block-aligned input
+        let ciphertext = encrypt_cbc(&key, &iv, b"sixteen byte msg").unwrap();
+        assert_eq!(
+            hex::encode(&ciphertext),
+            "8caba8d9594fb18cbeea69a1abca1c9e2756502bf06a43f5353294bb6da7685a"
+        );
+
+        let empty = encrypt_cbc(&key, &iv, b"").unwrap();
+        assert_eq!(hex::encode(&empty), "954f64f2e4e86e9eee82d20216684899");
+        assert_eq!(decrypt_cbc(&key, &iv, &empty).unwrap(), b"");
+
+        assert!(decrypt_cbc(&key, &iv, &ciphertext[..15]).is_err());
+        assert!(decrypt_cbc(&[1; 16], &iv, &ciphertext).is_err());
     }
 
     #[test]
@@ -487,25 +685,190 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_prove_and_verify_ultra_honk() {
+    async fn test_get_honk_verification_key() {
         setup_srs_from_bytecode(BYTECODE, None, true).await.unwrap();
 
+        let vk = get_honk_verification_key(BYTECODE, OracleHashFlavor::Poseidon2).unwrap();
+        assert!(!vk.is_empty());
+    }
+
+    #[test]
+    fn test_prove_ultra_honk_rejects_wrong_pub_inputs_amount() {
         let initial_witness = from_vec_to_witness_map(vec![5 as u128, 6 as u128]).unwrap();
+        let err = prove_ultra_honk(
+            BYTECODE,
+            initial_witness,
+            Some(2),
+            OracleHashFlavor::Poseidon2,
+            PrintOutput::None,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            BbError::PublicInputsMismatch {
+                expected: 2,
+                actual: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn test_from_vec_str_to_witness_map_is_strict() {
+        let witness = from_vec_str_to_witness_map(vec!["0x2a", "0b11", "-1"]).unwrap();
+        assert_eq!(witness[&Witness(0)], FieldElement::from(42u128));
+        assert_eq!(witness[&Witness(1)], FieldElement::from(3u128));
+        assert_eq!(witness[&Witness(2)], -FieldElement::one());
+
+        for bad in ["0x2_a", "1__0", "+7", "-+7", " 7", "7\n"] {
+            assert!(from_vec_str_to_witness_map(vec![bad]).is_err(), "{:?}", bad);
+        }
+
+        let err = from_vec_str_to_witness_map(vec!["1", "0xzz"]).unwrap_err();
+        assert!(
+            matches!(err, BbError::InvalidWitness { index: 1, ref value, .. } if value == "0xzz")
+        );
 
-        let ProofResponse {
-            public_inputs,
-            raw_proof,
-            complete_data: _,
-        } = prove_ultra_honk(BYTECODE, initial_witness, 1, true).expect("prove_ultra_honk failed");
+        let modulus =
+            "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+        assert!(from_vec_str_to_witness_map(vec![modulus]).is_err());
+    }
 
-        println!("Public input {:?}", public_inputs);
-        println!("Raw proof {:?}", raw_proof);
+    #[test]
+    fn test_execute_failure_reports_opcode_location() {
+        // The circuit asserts x != y
+        let initial_witness = from_vec_to_witness_map(vec![5 as u128, 5 as u128]).unwrap();
+        match execute(BYTECODE, initial_witness, PrintOutput::None) {
+            Err(BbError::Execution(err)) => {
+                assert!(!err.call_stack.is_empty());
+                assert!(err.source_locations.is_empty());
+            }
+            other => panic!("expected an execution error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_ultra_honk() {
+        setup_srs_from_bytecode(BYTECODE, None, false)
+            .await
+            .unwrap();
+
+        let initial_witness = from_vec_to_witness_map(vec![5 as u128, 6 as u128]).unwrap();
+        let mut proof = prove_ultra_honk(
+            BYTECODE,
+            initial_witness,
+            None,
+            OracleHashFlavor::Poseidon2,
+            PrintOutput::None,
+        )
+        .unwrap();
+        let vk = VerificationKey::from_bytecode(BYTECODE, OracleHashFlavor::Poseidon2).unwrap();
+
+        verify_ultra_honk(&proof, &vk, OracleHashFlavor::Poseidon2).unwrap();
+
+        proof.public_inputs[0] = proof.public_inputs[0] + FieldElement::one();
+        assert!(matches!(
+            verify_ultra_honk(&proof, &vk, OracleHashFlavor::Poseidon2),
+            Err(BbError::VerificationFailed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_versioned_proof_and_vk_round_trip() {
+        setup_srs_from_bytecode(BYTECODE, None, false)
+            .await
+            .unwrap();
+
+        let initial_witness = from_vec_to_witness_map(vec![5 as u128, 6 as u128]).unwrap();
+        let proof = prove_ultra_honk(
+            BYTECODE,
+            initial_witness,
+            None,
+            OracleHashFlavor::Poseidon2,
+            PrintOutput::None,
+        )
+        .unwrap();
+        let vk = VerificationKey::from_bytecode(BYTECODE, OracleHashFlavor::Poseidon2).unwrap();
+
+        let mut proof = ProofResponse::from_bytes(&proof.to_bytes()).unwrap();
+        let vk = VerificationKey::from_versioned_bytes(&vk.to_versioned_bytes()).unwrap();
+        verify_ultra_honk(&proof, &vk, OracleHashFlavor::Poseidon2).unwrap();
+
+        proof.bb_version.version = "0.1.0".to_string();
+        assert!(matches!(
+            verify_ultra_honk(&proof, &vk, OracleHashFlavor::Poseidon2),
+            Err(BbError::VersionMismatch { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_ultra_honk_all_flavors() {
+        setup_srs_from_bytecode(BYTECODE, None, false)
+            .await
+            .unwrap();
+
+        for flavor in [
+            OracleHashFlavor::Poseidon2,
+            OracleHashFlavor::Keccak,
+            OracleHashFlavor::Starknet,
+        ]
+        .into_iter()
+        .filter(|flavor| flavor.is_available())
+        {
+            let initial_witness = from_vec_to_witness_map(vec![5 as u128, 6 as u128]).unwrap();
+            let proof =
+                prove_ultra_honk(BYTECODE, initial_witness, None, flavor, PrintOutput::None)
+                    .unwrap();
+            let vk = VerificationKey::from_bytecode(BYTECODE, flavor).unwrap();
+            verify_ultra_honk(&proof, &vk, flavor).unwrap();
+        }
+    }
+
+    #[cfg(not(bb_starknet))]
+    #[test]
+    fn test_starknet_flavor_unavailable() {
+        assert!(matches!(
+            get_honk_verification_key(BYTECODE, OracleHashFlavor::Starknet),
+            Err(BbError::FlavorUnavailable(OracleHashFlavor::Starknet))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_prove_and_verify_ultra_honk() {
+        setup_srs_from_bytecode(BYTECODE, None, true).await.unwrap();
+
+        let initial_witness = from_vec_to_witness_map(vec![5 as u128, 6 as u128]).unwrap();
+
+        let proof_response = prove_ultra_honk(
+            BYTECODE,
+            initial_witness,
+            Some(1),
+            OracleHashFlavor::Keccak,
+            PrintOutput::None,
+        )
+        .expect("prove_ultra_honk failed");
+        let public_inputs = proof_response.public_inputs_bytes();
+        let ProofResponse { raw_proof, .. } = proof_response;
 
         let vk_bytes: Vec<u8> = fs::read("vk.bin").unwrap();
         let vk = HonkVerificationKey::from_bytes(&vk_bytes).unwrap();
 
         let proof = HonkProof::from_bytes(&raw_proof, &public_inputs).unwrap();
-        let calldata = get_honk_calldata(&proof, &vk, HonkFlavor::KECCAK).unwrap();
-        println!("calldata {:?}", calldata);
+        let calldata = get_honk_calldata(
+            &proof,
+            &vk,
+            OracleHashFlavor::Keccak.garaga_flavor().unwrap(),
+        )
+        .unwrap();
+        assert!(!calldata.is_empty());
+    }
+
+    #[cfg(feature = "dynamic")]
+    #[test]
+    fn test_loaded_library_version() {
+        use crate::barretenberg::dynamic::{check_loaded_version, loaded_library_path};
+
+        let path = loaded_library_path().expect("barretenberg is not dynamically loaded");
+        assert!(path.to_string_lossy().contains("libbarretenberg.so"));
+        check_loaded_version().unwrap();
     }
 }
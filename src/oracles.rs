@@ -0,0 +1,263 @@
+//! Foreign call (`#[oracle]`) handlers for circuit execution, local or over JSON-RPC
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use acir::{
+    brillig::{ForeignCallParam, ForeignCallResult},
+    FieldElement,
+};
+use acvm::pwg::ForeignCallWaitInfo;
+pub use nargo::foreign_calls::print::PrintOutput;
+use nargo::foreign_calls::{
+    default::DefaultForeignCallBuilder, rpc::RPCForeignCallExecutor, ForeignCallError,
+    ForeignCallExecutor,
+};
+
+/// Handler for one oracle, called with the oracle's flattened arguments
+pub type OracleHandler = Box<
+    dyn FnMut(
+        &[ForeignCallParam<FieldElement>],
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallError>,
+>;
+
+/// Rust closures registered by oracle name
+///
+/// On its own the registry only answers its registered oracles. Use
+/// [`OracleRegistry::with_defaults`] to fall through to nargo's `print` and
+/// mocking handlers, and [`OracleRegistry::with_resolver`] to send the
+/// remaining calls to a JSON-RPC oracle server. Registered names always take
+/// precedence.
+#[derive(Default)]
+pub struct OracleRegistry<'a> {
+    handlers: HashMap<String, OracleHandler>,
+    defaults: Option<Box<dyn ForeignCallExecutor<FieldElement> + 'a>>,
+    resolver: Option<RpcOracleResolver>,
+}
+
+impl<'a> OracleRegistry<'a> {
+    pub fn new() -> Self {
+        OracleRegistry::default()
+    }
+
+    /// Register `handler` for the oracle called `name`, replacing any previous one
+    pub fn register<H>(&mut self, name: impl Into<String>, handler: H) -> &mut Self
+    where
+        H: FnMut(
+                &[ForeignCallParam<FieldElement>],
+            ) -> Result<ForeignCallResult<FieldElement>, ForeignCallError>
+            + 'static,
+    {
+        self.handlers.insert(name.into(), Box::new(handler));
+        self
+    }
+
+    /// Whether an oracle called `name` is registered
+    pub fn handles(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    /// Send calls no handler answers to `resolver`
+    pub fn with_resolver(mut self, resolver: RpcOracleResolver) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Fall through to nargo's default `print` and mock handlers for unregistered names
+    pub fn with_defaults(mut self) -> Self {
+        self.defaults = Some(Box::new(DefaultForeignCallBuilder::default().build()));
+        self
+    }
+
+    /// Like [`OracleRegistry::with_defaults`], sending `print` output to `output`
+    /// instead of stdout, e.g. `PrintOutput::String(&mut captured)`
+    pub fn with_print_output(mut self, output: PrintOutput<'a>) -> Self {
+        self.defaults = Some(Box::new(
+            DefaultForeignCallBuilder::default()
+                .with_output(output)
+                .build(),
+        ));
+        self
+    }
+}
+
+impl ForeignCallExecutor<FieldElement> for OracleRegistry<'_> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallError> {
+        if let Some(handler) = self.handlers.get_mut(&foreign_call.function) {
+            return handler(&foreign_call.inputs);
+        }
+        if let Some(defaults) = &mut self.defaults {
+            match defaults.execute(foreign_call) {
+                Err(ForeignCallError::NoHandler(_)) => {}
+                result => return result,
+            }
+        }
+        match &mut self.resolver {
+            Some(resolver) => resolver.execute(foreign_call),
+            None => Err(ForeignCallError::NoHandler(foreign_call.function.clone())),
+        }
+    }
+}
+
+/// Resolves foreign calls against a JSON-RPC oracle server, like nargo's `--oracle-resolver`
+///
+/// Wraps nargo's `RPCForeignCallExecutor`, which blocks on its own runtime, so
+/// execute from a blocking context (e.g. `tokio::task::spawn_blocking`) when
+/// inside an async runtime. Each call times out after `NARGO_FOREIGN_CALL_TIMEOUT`
+/// milliseconds when that is set as the resolver is created.
+pub struct RpcOracleResolver(RPCForeignCallExecutor);
+
+impl RpcOracleResolver {
+    /// Resolver for the server at `url`
+    ///
+    /// # Panics
+    ///
+    /// If `url` is not a valid HTTP URL
+    pub fn new(url: &str) -> Self {
+        static NEXT_SESSION: AtomicU64 = AtomicU64::new(0);
+        let session_id = NEXT_SESSION.fetch_add(1, Ordering::Relaxed);
+        RpcOracleResolver(RPCForeignCallExecutor::new(url, session_id, None, None))
+    }
+}
+
+impl ForeignCallExecutor<FieldElement> for RpcOracleResolver {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallError> {
+        self.0.execute(foreign_call)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn call(function: &str, input: u128) -> ForeignCallWaitInfo<FieldElement> {
+        ForeignCallWaitInfo {
+            function: function.to_string(),
+            inputs: vec![ForeignCallParam::Single(FieldElement::from(input))],
+        }
+    }
+
+    #[test]
+    fn dispatches_by_name() {
+        let mut registry = OracleRegistry::new();
+        registry.register("double", |inputs| match inputs {
+            [ForeignCallParam::Single(value)] => Ok((*value + *value).into()),
+            _ => Err(ForeignCallError::MissingForeignCallInputs),
+        });
+        assert!(registry.handles("double"));
+
+        let result = registry.execute(&call("double", 21)).unwrap();
+        assert_eq!(
+            result.values,
+            vec![ForeignCallParam::Single(FieldElement::from(42u128))]
+        );
+
+        assert!(matches!(
+            registry.execute(&call("triple", 1)),
+            Err(ForeignCallError::NoHandler(name)) if name == "triple"
+        ));
+    }
+
+    #[test]
+    fn registered_handlers_take_precedence_over_defaults() {
+        let mut printed = String::new();
+        let mut registry =
+            OracleRegistry::new().with_print_output(PrintOutput::String(&mut printed));
+        registry.register("print", |_| Ok(FieldElement::from(7u128).into()));
+
+        let result = registry.execute(&call("print", 1)).unwrap();
+        assert_eq!(
+            result.values,
+            vec![ForeignCallParam::Single(FieldElement::from(7u128))]
+        );
+        drop(registry);
+        assert!(printed.is_empty());
+    }
+
+    /// Stand-in oracle server answering each request with `respond(params)`
+    /// after `delay`, returning its URL
+    fn serve(delay: Duration, respond: fn(&Value) -> Value) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut reader = BufReader::new(stream.unwrap());
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: Value = serde_json::from_slice(&body).unwrap();
+                let response = json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": respond(&request["params"][0]),
+                })
+                .to_string();
+
+                thread::sleep(delay);
+                let _ = write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                );
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn resolves_unregistered_calls_over_rpc() {
+        let url = serve(Duration::ZERO, |params| {
+            assert_eq!(params["function_call"]["function"], "merkle_path");
+            json!({ "values": [["0x01", "0x02"]] })
+        });
+        let mut registry = OracleRegistry::new().with_resolver(RpcOracleResolver::new(&url));
+
+        let result = registry.execute(&call("merkle_path", 7)).unwrap();
+        assert_eq!(
+            result.values,
+            vec![ForeignCallParam::Array(vec![
+                FieldElement::from(1u128),
+                FieldElement::from(2u128)
+            ])]
+        );
+    }
+
+    #[test]
+    fn resolver_calls_time_out() {
+        let url = serve(Duration::from_secs(2), |_| json!({ "values": [] }));
+        std::env::set_var("NARGO_FOREIGN_CALL_TIMEOUT", "100");
+        let mut resolver = RpcOracleResolver::new(&url);
+        std::env::remove_var("NARGO_FOREIGN_CALL_TIMEOUT");
+
+        assert!(matches!(
+            resolver.execute(&call("merkle_path", 7)),
+            Err(ForeignCallError::ExternalResolverError(_))
+        ));
+    }
+}
@@ -1,13 +1,21 @@
-use libc::free;
-use std::ffi::c_void;
-use std::ptr;
-
-use acir::{native_types::WitnessMap, FieldElement};
+use acir::{
+    circuit::Program,
+    native_types::{Witness, WitnessMap},
+    AcirField, FieldElement,
+};
 
+#[cfg(bb_starknet)]
+use crate::bindgen::acir_prove_ultra_starknet_honk;
 use crate::{
+    barretenberg::version::{self, BbVersion, Reader},
     bindgen::{acir_prove_ultra_honk, acir_prove_ultra_keccak_honk},
-    circuits::get_acir_buffer_uncompressed,
+    buffer::BbBuffer,
+    circuits::{get_acir_buffer_uncompressed, get_program},
+    error::BbError,
     execute::execute,
+    flavor::OracleHashFlavor,
+    fr::Fr,
+    oracles::PrintOutput,
     witness::serialize_witness,
 };
 
@@ -19,21 +27,135 @@ fn encode_raw_buffer(data: &[u8]) -> Vec<u8> {
 }
 
 const LEN_PREFIX: usize = 4;
+const PROOF_MAGIC: &[u8; 4] = b"BBPF";
 
 #[derive(Debug)]
 pub struct ProofResponse {
-    pub public_inputs: Vec<u8>,
+    /// Public input values, in the order barretenberg places them in the proof
+    pub public_inputs: Vec<FieldElement>,
+    /// Witness index of each entry in `public_inputs`
+    pub public_input_witnesses: Vec<Witness>,
     pub raw_proof: Vec<u8>,
     pub complete_data: Vec<u8>,
+    /// barretenberg the proof was created with
+    pub bb_version: BbVersion,
+}
+
+impl ProofResponse {
+    /// Public inputs as concatenated 32-byte big-endian words
+    pub fn public_inputs_bytes(&self) -> Vec<u8> {
+        self.public_inputs
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect()
+    }
+
+    /// Public inputs as field elements, e.g. for `abi::decode_public_inputs`
+    pub fn public_inputs_fr(&self) -> Vec<Fr> {
+        self.public_inputs
+            .iter()
+            .map(|&value| Fr::from(value))
+            .collect()
+    }
+
+    /// Serialize the proof, tagged with the barretenberg version that created it
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&(self.public_inputs.len() as u32).to_be_bytes());
+        payload.extend_from_slice(&self.public_inputs_bytes());
+        for witness in &self.public_input_witnesses {
+            payload.extend_from_slice(&witness.0.to_be_bytes());
+        }
+        for data in [&self.raw_proof, &self.complete_data] {
+            payload.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            payload.extend_from_slice(data);
+        }
+        version::wrap(PROOF_MAGIC, &self.bb_version, &payload)
+    }
+
+    /// Read a proof written by `to_bytes`
+    ///
+    /// Compatibility with the linked barretenberg is checked on verification,
+    /// not here.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbError> {
+        let (bb_version, payload) = version::unwrap(PROOF_MAGIC, bytes)?;
+        let mut reader = Reader::new(payload, PROOF_MAGIC);
+        let count = reader.u32()? as usize;
+        let public_inputs = reader
+            .take(32 * count)?
+            .chunks_exact(32)
+            .map(FieldElement::from_be_bytes_reduce)
+            .collect();
+        let public_input_witnesses = (0..count)
+            .map(|_| reader.u32().map(Witness))
+            .collect::<Result<_, _>>()?;
+        let raw_proof = reader.bytes()?.to_vec();
+        let complete_data = reader.bytes()?.to_vec();
+        if !reader.rest().is_empty() {
+            return Err(BbError::Envelope("trailing bytes after proof".to_string()));
+        }
+        Ok(ProofResponse {
+            public_inputs,
+            public_input_witnesses,
+            raw_proof,
+            complete_data,
+            bb_version,
+        })
+    }
 }
 
+/// Witnesses exposed as public inputs by the main function: public parameters
+/// followed by return values, matching barretenberg's layout
+///
+/// # Arguments
+///
+/// * program: The program to read the public inputs from
+///
+/// # Returns
+///
+/// The public input witnesses
+pub fn get_public_input_witnesses(program: &Program<FieldElement>) -> Vec<Witness> {
+    let main = &program.functions[0];
+    main.public_parameters
+        .0
+        .iter()
+        .chain(main.return_values.0.iter())
+        .copied()
+        .collect()
+}
+
+/// Execute the circuit and create an UltraHonk proof
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode to prove
+/// * initial_witness: The initial witness to use for the execution
+/// * expected_pub_inputs: If set, the number of public inputs the caller expects the circuit to have
+/// * flavor: The transcript hash to prove with
+/// * print_output: Where `print`/`println` output of the execution goes
+///
+/// # Returns
+///
+/// The proof split into public inputs and the raw proof
 pub fn prove_ultra_honk(
     circuit_bytecode: &str,
     initial_witness: WitnessMap<FieldElement>,
-    pub_inputs_amount: usize,
-    isKeccak: bool,
-) -> Result<ProofResponse, String> {
-    let witness_stack = execute(circuit_bytecode, initial_witness)?;
+    expected_pub_inputs: Option<usize>,
+    flavor: OracleHashFlavor,
+    print_output: PrintOutput<'_>,
+) -> Result<ProofResponse, BbError> {
+    flavor.check_available()?;
+    let public_input_witnesses = get_public_input_witnesses(&get_program(circuit_bytecode)?);
+    if let Some(expected) = expected_pub_inputs {
+        if expected != public_input_witnesses.len() {
+            return Err(BbError::PublicInputsMismatch {
+                expected,
+                actual: public_input_witnesses.len(),
+            });
+        }
+    }
+
+    let witness_stack = execute(circuit_bytecode, initial_witness, print_output)?;
     let serialized_solved_witness = serialize_witness(witness_stack)?;
     let acir_buffer_uncompressed = get_acir_buffer_uncompressed(circuit_bytecode)?;
 
@@ -43,43 +165,56 @@ pub fn prove_ultra_honk(
     let acir_ptr = acir_input.as_ptr();
     let witness_ptr = witness_input.as_ptr();
 
-    let mut out_ptr: *mut u8 = ptr::null_mut();
-
-    if isKeccak {
-        unsafe {
-            acir_prove_ultra_keccak_honk(acir_ptr, witness_ptr, &mut out_ptr as *mut *mut u8);
-        }
-    } else {
-        unsafe {
-            acir_prove_ultra_honk(acir_ptr, witness_ptr, &mut out_ptr as *mut *mut u8);
-        }
+    let out = unsafe {
+        BbBuffer::from_out_ptr(|out_ptr| match flavor {
+            OracleHashFlavor::Poseidon2 => acir_prove_ultra_honk(acir_ptr, witness_ptr, out_ptr),
+            OracleHashFlavor::Keccak => {
+                acir_prove_ultra_keccak_honk(acir_ptr, witness_ptr, out_ptr)
+            }
+            #[cfg(bb_starknet)]
+            OracleHashFlavor::Starknet => {
+                acir_prove_ultra_starknet_honk(acir_ptr, witness_ptr, out_ptr)
+            }
+            #[cfg(not(bb_starknet))]
+            OracleHashFlavor::Starknet => unreachable!("rejected by check_available"),
+        })?
+    };
+
+    // Skip the outer prefix, then read the inner one
+    let inner = out.as_bytes();
+    if inner.len() < LEN_PREFIX {
+        return Err(BbError::FfiOutput(
+            "proof buffer is missing its inner length prefix".to_string(),
+        ));
     }
-
-    // Skip both 4-byte prefixes
-    let data_start = 2 * LEN_PREFIX; // 8 bytes
-
-    // Read len
-    let inner_len_be =
-        unsafe { std::ptr::read_unaligned(out_ptr.add(LEN_PREFIX) as *const [u8; LEN_PREFIX]) };
-    let inner_len = u32::from_be_bytes(inner_len_be) as usize;
-
-    // Copy data from 8th byte
-    let proof_with_pub_inputs =
-        unsafe { std::slice::from_raw_parts(out_ptr.add(data_start), inner_len).to_vec() };
+    let inner_len = u32::from_be_bytes([inner[0], inner[1], inner[2], inner[3]]) as usize;
+    let proof_with_pub_inputs = inner
+        .get(LEN_PREFIX..LEN_PREFIX + inner_len)
+        .ok_or_else(|| BbError::FfiOutput("proof buffer shorter than its prefix".to_string()))?
+        .to_vec();
 
     // Copy all (with prefixes) dla complete_data
-    let complete_data =
-        unsafe { std::slice::from_raw_parts(out_ptr, data_start + inner_len).to_vec() };
-
-    unsafe { free(out_ptr as *mut c_void) };
+    let complete_data = out.as_slice_with_prefix(1).to_vec();
 
     // Public inputs: each  32 bytes
-    let pub_inputs_total_len = 32 * pub_inputs_amount;
-    let public_inputs = proof_with_pub_inputs[0..pub_inputs_total_len].to_vec();
+    let pub_inputs_total_len = 32 * public_input_witnesses.len();
+    if proof_with_pub_inputs.len() < pub_inputs_total_len {
+        return Err(BbError::FfiOutput(format!(
+            "proof of {} bytes cannot hold {} public inputs",
+            proof_with_pub_inputs.len(),
+            public_input_witnesses.len()
+        )));
+    }
+    let public_inputs = proof_with_pub_inputs[..pub_inputs_total_len]
+        .chunks_exact(32)
+        .map(FieldElement::from_be_bytes_reduce)
+        .collect();
     let raw_proof = proof_with_pub_inputs[pub_inputs_total_len..].to_vec();
     Ok(ProofResponse {
         public_inputs,
+        public_input_witnesses,
         raw_proof,
         complete_data,
+        bb_version: version::version(),
     })
 }
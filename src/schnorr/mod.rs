@@ -0,0 +1,139 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{bindgen, encode_raw_buffer, error::BbError, fr::Fr};
+
+pub mod multisig;
+
+/// Grumpkin scalar field modulus (the BN254 base field), big-endian
+const GRUMPKIN_SCALAR_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+/// Grumpkin Schnorr private key
+#[derive(Clone, PartialEq, Eq)]
+pub struct PrivateKey([u8; 32]);
+
+impl PrivateKey {
+    /// Build a private key from 32 big-endian bytes, rejecting zero and values
+    /// at or above the Grumpkin scalar field modulus
+    pub fn from_bytes(bytes: [u8; 32]) -> Result<Self, BbError> {
+        if bytes == [0; 32] || bytes >= GRUMPKIN_SCALAR_MODULUS {
+            return Err(BbError::Schnorr(
+                "private key must be a non-zero Grumpkin scalar".to_string(),
+            ));
+        }
+        Ok(PrivateKey(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Derive the public key `private_key * G`
+    pub fn public_key(&self) -> PublicKey {
+        let mut out = [0u8; 64];
+        unsafe {
+            bindgen::schnorr_compute_public_key(self.0.as_ptr(), out.as_mut_ptr());
+        }
+        PublicKey::from_bytes_unchecked(out)
+    }
+
+    /// Sign a message
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        let message_buf = encode_raw_buffer(message);
+        let mut s = [0u8; 32];
+        let mut e = [0u8; 32];
+        unsafe {
+            bindgen::schnorr_construct_signature(
+                message_buf.as_ptr(),
+                self.0.as_ptr(),
+                s.as_mut_ptr(),
+                e.as_mut_ptr(),
+            );
+        }
+        Signature { s, e }
+    }
+}
+
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PrivateKey(..)")
+    }
+}
+
+/// Grumpkin Schnorr public key, an affine point whose coordinates live in the
+/// BN254 scalar field (the `pub_key_x` / `pub_key_y` inputs of Noir circuits)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicKey {
+    pub x: Fr,
+    pub y: Fr,
+}
+
+impl PublicKey {
+    pub(crate) fn from_bytes_unchecked(bytes: [u8; 64]) -> Self {
+        let mut x = [0u8; 32];
+        let mut y = [0u8; 32];
+        x.copy_from_slice(&bytes[..32]);
+        y.copy_from_slice(&bytes[32..]);
+        PublicKey {
+            x: Fr::from_be_bytes_unchecked(x),
+            y: Fr::from_be_bytes_unchecked(y),
+        }
+    }
+
+    /// `x || y`, the layout barretenberg serializes affine points with
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        out[..32].copy_from_slice(self.x.as_be_bytes());
+        out[32..].copy_from_slice(self.y.as_be_bytes());
+        out
+    }
+
+    /// Verify a signature over `message`
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> bool {
+        let message_buf = encode_raw_buffer(message);
+        let pub_key = self.to_bytes();
+        let mut ok = false;
+        unsafe {
+            bindgen::schnorr_verify_signature(
+                message_buf.as_ptr(),
+                pub_key.as_ptr(),
+                signature.s.as_ptr(),
+                signature.e.as_ptr(),
+                &mut ok,
+            );
+        }
+        ok
+    }
+}
+
+/// Schnorr signature `(s, e)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    pub s: [u8; 32],
+    pub e: [u8; 32],
+}
+
+impl Signature {
+    /// `s || e`, the `[u8; 64]` layout Noir's schnorr `verify_signature` takes
+    ///
+    /// Both the noir-lang `schnorr` library and barretenberg's ACIR
+    /// `schnorr_verify` read `s` from bytes `0..32` and `e` from bytes `32..64`.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        out[..32].copy_from_slice(&self.s);
+        out[32..].copy_from_slice(&self.e);
+        out
+    }
+
+    pub fn from_bytes(bytes: [u8; 64]) -> Self {
+        let mut s = [0u8; 32];
+        let mut e = [0u8; 32];
+        s.copy_from_slice(&bytes[..32]);
+        e.copy_from_slice(&bytes[32..]);
+        Signature { s, e }
+    }
+}
@@ -0,0 +1,286 @@
+//! Round-based Schnorr multisignatures over Grumpkin
+//!
+//! 1. Every party builds a [`MultisigSigner`] and publishes its [`MultisigPublicKey`].
+//! 2. Every party calls [`MultisigSigner::round_one`] and publishes the [`RoundOneCommitment`].
+//! 3. Once all commitments are in, every party calls [`RoundOneSigner::round_two`]
+//!    and publishes its [`RoundTwoShare`].
+//! 4. A [`MultisigAggregator`] combines the shares into a plain [`Signature`]
+//!    that verifies against [`MultisigAggregator::combined_public_key`].
+//!
+//! Commitments, shares and public keys must be passed in the same signer order
+//! everywhere.
+
+use serde::{Deserialize, Serialize};
+
+use super::{PrivateKey, PublicKey, Signature};
+use crate::{bindgen, encode_raw_buffer, error::BbError};
+
+/// Length-prefixed vector of fixed-size elements, as barretenberg's `vec_in_buf`
+fn encode_vector<const N: usize>(items: &[[u8; N]]) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(4 + items.len() * N);
+    buffer.extend_from_slice(&(items.len() as u32).to_be_bytes());
+    for item in items {
+        buffer.extend_from_slice(item);
+    }
+    buffer
+}
+
+fn check_success(success: bool, step: &str) -> Result<(), BbError> {
+    if success {
+        Ok(())
+    } else {
+        Err(BbError::Schnorr(format!("multisig {} failed", step)))
+    }
+}
+
+/// A signer's public key together with its proof of possession
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigPublicKey {
+    pub public_key: PublicKey,
+    proof_of_possession: [[u8; 32]; 2],
+}
+
+impl MultisigPublicKey {
+    fn to_bytes(&self) -> [u8; 128] {
+        let mut out = [0u8; 128];
+        out[..64].copy_from_slice(&self.public_key.to_bytes());
+        out[64..96].copy_from_slice(&self.proof_of_possession[0]);
+        out[96..].copy_from_slice(&self.proof_of_possession[1]);
+        out
+    }
+
+    fn from_bytes(bytes: [u8; 128]) -> Self {
+        let mut public_key = [0u8; 64];
+        let mut proof_of_possession = [[0u8; 32]; 2];
+        public_key.copy_from_slice(&bytes[..64]);
+        proof_of_possession[0].copy_from_slice(&bytes[64..96]);
+        proof_of_possession[1].copy_from_slice(&bytes[96..]);
+        MultisigPublicKey {
+            public_key: PublicKey::from_bytes_unchecked(public_key),
+            proof_of_possession,
+        }
+    }
+}
+
+/// Public nonce commitments `(R, S)` published in round one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundOneCommitment([[u8; 32]; 4]);
+
+impl RoundOneCommitment {
+    fn to_bytes(&self) -> [u8; 128] {
+        let mut out = [0u8; 128];
+        for (chunk, word) in out.chunks_exact_mut(32).zip(self.0.iter()) {
+            chunk.copy_from_slice(word);
+        }
+        out
+    }
+
+    fn from_bytes(bytes: [u8; 128]) -> Self {
+        let mut words = [[0u8; 32]; 4];
+        for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(32)) {
+            word.copy_from_slice(chunk);
+        }
+        RoundOneCommitment(words)
+    }
+}
+
+/// A signer's signature share published in round two
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundTwoShare([u8; 32]);
+
+/// One party of a multisignature, holding its long-term private key
+#[derive(Debug, Clone)]
+pub struct MultisigSigner {
+    private_key: PrivateKey,
+    public_key: MultisigPublicKey,
+}
+
+impl MultisigSigner {
+    pub fn new(private_key: PrivateKey) -> Self {
+        let mut out = [0u8; 128];
+        unsafe {
+            bindgen::schnorr_multisig_create_multisig_public_key(
+                private_key.as_bytes().as_ptr(),
+                out.as_mut_ptr(),
+            );
+        }
+        MultisigSigner {
+            private_key,
+            public_key: MultisigPublicKey::from_bytes(out),
+        }
+    }
+
+    /// The key to publish to the other signers and the aggregator
+    pub fn public_key(&self) -> MultisigPublicKey {
+        self.public_key
+    }
+
+    /// Draw fresh nonces and commit to them
+    ///
+    /// The returned [`RoundOneSigner`] keeps the private nonces and is consumed
+    /// by round two, so a nonce can never sign twice.
+    pub fn round_one(&self) -> (RoundOneSigner<'_>, RoundOneCommitment) {
+        let mut public_out = [0u8; 128];
+        let mut private_out = [0u8; 64];
+        unsafe {
+            bindgen::schnorr_multisig_construct_signature_round_1(
+                public_out.as_mut_ptr(),
+                private_out.as_mut_ptr(),
+            );
+        }
+        (
+            RoundOneSigner {
+                signer: self,
+                private_nonces: private_out,
+            },
+            RoundOneCommitment::from_bytes(public_out),
+        )
+    }
+}
+
+/// A signer that has published its round one commitment
+pub struct RoundOneSigner<'a> {
+    signer: &'a MultisigSigner,
+    private_nonces: [u8; 64],
+}
+
+impl RoundOneSigner<'_> {
+    /// Produce this signer's share once every commitment has been collected
+    ///
+    /// # Arguments
+    ///
+    /// * message: The message being signed
+    /// * signer_public_keys: The public keys of all signers
+    /// * commitments: The round one commitments of all signers, in the same order
+    pub fn round_two(
+        self,
+        message: &[u8],
+        signer_public_keys: &[MultisigPublicKey],
+        commitments: &[RoundOneCommitment],
+    ) -> Result<RoundTwoShare, BbError> {
+        if signer_public_keys.len() != commitments.len() {
+            return Err(BbError::Schnorr(format!(
+                "{} signers but {} round one commitments",
+                signer_public_keys.len(),
+                commitments.len()
+            )));
+        }
+
+        let message_buf = encode_raw_buffer(message);
+        let pubkeys_buf = encode_vector(
+            &signer_public_keys
+                .iter()
+                .map(MultisigPublicKey::to_bytes)
+                .collect::<Vec<_>>(),
+        );
+        let round_one_buf = encode_vector(
+            &commitments
+                .iter()
+                .map(RoundOneCommitment::to_bytes)
+                .collect::<Vec<_>>(),
+        );
+        let mut out = [0u8; 32];
+        let mut success = false;
+        unsafe {
+            bindgen::schnorr_multisig_construct_signature_round_2(
+                message_buf.as_ptr(),
+                self.signer.private_key.as_bytes().as_ptr(),
+                self.private_nonces.as_ptr(),
+                pubkeys_buf.as_ptr(),
+                round_one_buf.as_ptr(),
+                out.as_mut_ptr(),
+                &mut success,
+            );
+        }
+        check_success(success, "round two")?;
+        Ok(RoundTwoShare(out))
+    }
+}
+
+/// Collects the shares of a fixed signer set into a single signature
+#[derive(Debug, Clone)]
+pub struct MultisigAggregator {
+    signer_public_keys: Vec<MultisigPublicKey>,
+    combined_public_key: PublicKey,
+}
+
+impl MultisigAggregator {
+    /// Validate every proof of possession and combine the signer keys
+    pub fn new(signer_public_keys: Vec<MultisigPublicKey>) -> Result<Self, BbError> {
+        let pubkeys_buf = encode_vector(
+            &signer_public_keys
+                .iter()
+                .map(MultisigPublicKey::to_bytes)
+                .collect::<Vec<_>>(),
+        );
+        let mut out = [0u8; 64];
+        let mut success = false;
+        unsafe {
+            bindgen::schnorr_multisig_validate_and_combine_signer_pubkeys(
+                pubkeys_buf.as_ptr(),
+                out.as_mut_ptr(),
+                &mut success,
+            );
+        }
+        check_success(success, "public key validation")?;
+        Ok(MultisigAggregator {
+            signer_public_keys,
+            combined_public_key: PublicKey::from_bytes_unchecked(out),
+        })
+    }
+
+    /// The key the combined signature verifies against
+    pub fn combined_public_key(&self) -> PublicKey {
+        self.combined_public_key
+    }
+
+    /// Combine all round one commitments and round two shares into a signature
+    pub fn combine(
+        &self,
+        message: &[u8],
+        commitments: &[RoundOneCommitment],
+        shares: &[RoundTwoShare],
+    ) -> Result<Signature, BbError> {
+        let signers = self.signer_public_keys.len();
+        if commitments.len() != signers || shares.len() != signers {
+            return Err(BbError::Schnorr(format!(
+                "{} signers but {} commitments and {} shares",
+                signers,
+                commitments.len(),
+                shares.len()
+            )));
+        }
+
+        let message_buf = encode_raw_buffer(message);
+        let pubkeys_buf = encode_vector(
+            &self
+                .signer_public_keys
+                .iter()
+                .map(MultisigPublicKey::to_bytes)
+                .collect::<Vec<_>>(),
+        );
+        let round_one_buf = encode_vector(
+            &commitments
+                .iter()
+                .map(RoundOneCommitment::to_bytes)
+                .collect::<Vec<_>>(),
+        );
+        let round_two_buf = encode_vector(&shares.iter().map(|share| share.0).collect::<Vec<_>>());
+        let mut s = [0u8; 32];
+        let mut e = [0u8; 32];
+        let mut success = false;
+        unsafe {
+            bindgen::schnorr_multisig_combine_signatures(
+                message_buf.as_ptr(),
+                pubkeys_buf.as_ptr(),
+                round_one_buf.as_ptr(),
+                round_two_buf.as_ptr(),
+                s.as_mut_ptr(),
+                e.as_mut_ptr(),
+                &mut success,
+            );
+        }
+        check_success(success, "signature combination")?;
+        Ok(Signature { s, e })
+    }
+}
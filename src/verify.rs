@@ -0,0 +1,118 @@
+#[cfg(bb_starknet)]
+use crate::acir_verify_ultra_starknet_honk_safe;
+use crate::{
+    acir_verify_ultra_honk_safe, acir_verify_ultra_keccak_honk_safe,
+    barretenberg::{
+        utils::get_honk_verification_key,
+        version::{self, BbVersion},
+    },
+    error::BbError,
+    flavor::OracleHashFlavor,
+    prove::ProofResponse,
+};
+
+const VK_MAGIC: &[u8; 4] = b"BBVK";
+
+/// Serialized UltraHonk verification key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationKey {
+    bytes: Vec<u8>,
+    bb_version: BbVersion,
+}
+
+impl VerificationKey {
+    /// Wrap a raw verification key produced by barretenberg (e.g. read from `vk.bin`)
+    ///
+    /// Unchecked: raw keys carry no version, so the key is stamped with the
+    /// linked barretenberg and `verify_ultra_honk` can never reject it as
+    /// incompatible. Prefer `from_versioned_bytes` for keys stored by this crate.
+    pub fn from_unversioned_bytes(bytes: Vec<u8>) -> Self {
+        VerificationKey::from_linked(bytes)
+    }
+
+    /// Key just computed by the linked barretenberg
+    fn from_linked(bytes: Vec<u8>) -> Self {
+        VerificationKey {
+            bytes,
+            bb_version: version::version(),
+        }
+    }
+
+    /// Serialize the key, tagged with the barretenberg version that created it
+    pub fn to_versioned_bytes(&self) -> Vec<u8> {
+        version::wrap(VK_MAGIC, &self.bb_version, &self.bytes)
+    }
+
+    /// Read a key written by `to_versioned_bytes`
+    pub fn from_versioned_bytes(bytes: &[u8]) -> Result<Self, BbError> {
+        let (bb_version, payload) = version::unwrap(VK_MAGIC, bytes)?;
+        Ok(VerificationKey {
+            bytes: payload.to_vec(),
+            bb_version,
+        })
+    }
+
+    /// Compute the verification key of a circuit for the given transcript flavor
+    pub fn from_bytecode(
+        circuit_bytecode: &str,
+        flavor: OracleHashFlavor,
+    ) -> Result<Self, BbError> {
+        get_honk_verification_key(circuit_bytecode, flavor).map(VerificationKey::from_linked)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// barretenberg the key was created with
+    pub fn bb_version(&self) -> &BbVersion {
+        &self.bb_version
+    }
+}
+
+/// Verify a proof produced by `prove::prove_ultra_honk`
+///
+/// # Arguments
+///
+/// * proof: The proof to verify
+/// * vk: The verification key of the circuit
+/// * flavor: The transcript hash the proof was created with
+///
+/// # Returns
+///
+/// Ok if the proof is valid, `BbError::VersionMismatch` if the proof or key
+/// comes from an incompatible barretenberg, `BbError::VerificationFailed` otherwise
+pub fn verify_ultra_honk(
+    proof: &ProofResponse,
+    vk: &VerificationKey,
+    flavor: OracleHashFlavor,
+) -> Result<(), BbError> {
+    version::check_compatible(&proof.bb_version)?;
+    version::check_compatible(&vk.bb_version)?;
+
+    // barretenberg expects the public inputs in front of the proof, as it emitted them
+    let mut proof_with_pub_inputs = proof.public_inputs_bytes();
+    proof_with_pub_inputs.extend_from_slice(&proof.raw_proof);
+
+    flavor.check_available()?;
+    let verified = match flavor {
+        OracleHashFlavor::Poseidon2 => {
+            acir_verify_ultra_honk_safe(&proof_with_pub_inputs, vk.as_bytes())
+        }
+        OracleHashFlavor::Keccak => {
+            acir_verify_ultra_keccak_honk_safe(&proof_with_pub_inputs, vk.as_bytes())
+        }
+        #[cfg(bb_starknet)]
+        OracleHashFlavor::Starknet => {
+            acir_verify_ultra_starknet_honk_safe(&proof_with_pub_inputs, vk.as_bytes())
+        }
+        #[cfg(not(bb_starknet))]
+        OracleHashFlavor::Starknet => unreachable!("rejected by check_available"),
+    };
+
+    if verified {
+        Ok(())
+    } else {
+        Err(BbError::VerificationFailed)
+    }
+}
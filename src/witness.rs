@@ -3,6 +3,8 @@ use acir::{
     FieldElement,
 };
 
+use crate::{error::BbError, fr::Fr};
+
 /// Convert a vector of field elements to a witness map
 ///
 /// # Arguments
@@ -13,7 +15,7 @@ use acir::{
 /// # Returns
 ///
 /// The witness map
-pub fn from_vec_to_witness_map<T>(witness_vec: Vec<T>) -> Result<WitnessMap<FieldElement>, String>
+pub fn from_vec_to_witness_map<T>(witness_vec: Vec<T>) -> Result<WitnessMap<FieldElement>, BbError>
 where
     T: Copy,
     FieldElement: From<T>,
@@ -32,21 +34,24 @@ where
 /// # Arguments
 ///
 /// * witness_vec: The vector of strings to convert to a witness map
-/// Each string is expected to be a valid hexadecimal or decimal string
+/// Each string is a decimal or `0x`/`0o`/`0b`-prefixed value below the field
+/// modulus, optionally negative (`-x` becomes `p - x`)
 ///
 /// # Returns
 ///
-/// The witness map
+/// The witness map, or `BbError::InvalidWitness` naming the first bad string
 pub fn from_vec_str_to_witness_map(
     witness_vec: Vec<&str>,
-) -> Result<WitnessMap<FieldElement>, String> {
+) -> Result<WitnessMap<FieldElement>, BbError> {
     let mut witness_map = WitnessMap::new();
 
     for (i, witness) in witness_vec.iter().enumerate() {
-        witness_map.insert(
-            Witness(i as u32),
-            FieldElement::try_from_str(*witness).unwrap_or_default(),
-        );
+        let value = Fr::from_signed_str(witness).map_err(|e| BbError::InvalidWitness {
+            index: i,
+            value: witness.to_string(),
+            reason: e.to_string(),
+        })?;
+        witness_map.insert(Witness(i as u32), FieldElement::from(value));
     }
 
     Ok(witness_map)
@@ -63,8 +68,9 @@ pub fn from_vec_str_to_witness_map(
 /// The witness stack
 pub fn witness_map_to_witness_stack(
     witness_map: WitnessMap<FieldElement>,
-) -> Result<WitnessStack<FieldElement>, String> {
-    let witness_stack = WitnessStack::try_from(witness_map).map_err(|e| e.to_string())?;
+) -> Result<WitnessStack<FieldElement>, BbError> {
+    let witness_stack = WitnessStack::try_from(witness_map)
+        .map_err(|e| BbError::WitnessSerialization(e.to_string()))?;
     Ok(witness_stack)
 }
 
@@ -77,8 +83,9 @@ pub fn witness_map_to_witness_stack(
 /// # Returns
 ///
 /// The serialized witness stack
-pub fn serialize_witness(witness_stack: WitnessStack<FieldElement>) -> Result<Vec<u8>, String> {
-    let serialized_witness = bincode::serialize(&witness_stack).map_err(|e| e.to_string())?;
+pub fn serialize_witness(witness_stack: WitnessStack<FieldElement>) -> Result<Vec<u8>, BbError> {
+    let serialized_witness = bincode::serialize(&witness_stack)
+        .map_err(|e| BbError::WitnessSerialization(e.to_string()))?;
 
     Ok(serialized_witness)
 }
@@ -94,8 +101,8 @@ pub fn serialize_witness(witness_stack: WitnessStack<FieldElement>) -> Result<Ve
 /// The witness stack
 pub fn deserialize_witness(
     serialized_witness_stack: Vec<u8>,
-) -> Result<WitnessStack<FieldElement>, String> {
-    let witness_stack =
-        bincode::deserialize(&serialized_witness_stack).map_err(|e| e.to_string())?;
+) -> Result<WitnessStack<FieldElement>, BbError> {
+    let witness_stack = bincode::deserialize(&serialized_witness_stack)
+        .map_err(|e| BbError::WitnessSerialization(e.to_string()))?;
     Ok(witness_stack)
 }